@@ -7,7 +7,9 @@ use cw721::{
     AllNftInfoResponse, ApprovedForAllResponse, ContractInfoResponse, NftInfoResponse,
     NumTokensResponse, OwnerOfResponse, TokensResponse,
 };
-use terra_peep721::{ExecuteMsg, Extension, InstantiateMsg, MinterResponse, QueryMsg};
+use terra_peep721::{
+    ExecuteMsg, Extension, InstantiateMsg, MinterResponse, QueryMsg, TokensWithInfoResponse,
+};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -34,4 +36,9 @@ fn main() {
     export_schema(&schema_for!(NumTokensResponse), &out_dir);
     export_schema(&schema_for!(OwnerOfResponse), &out_dir);
     export_schema(&schema_for!(TokensResponse), &out_dir);
+    export_schema_with_title(
+        &schema_for!(TokensWithInfoResponse<Extension>),
+        &out_dir,
+        "TokensWithInfoResponse",
+    );
 }
@@ -0,0 +1,274 @@
+use cosmwasm_std::{Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::extension::MetaDataPersonalization;
+use crate::msg::{AuditCursor, AuditSection, IntegrityIssue};
+use crate::state::Cw721Contract;
+use crate::uri::uri_key;
+
+/// One page of the walk: what was wrong, and where to pick up next (None when done)
+pub struct AuditPage {
+    pub issues: Vec<IntegrityIssue>,
+    pub next: Option<AuditCursor>,
+}
+
+impl<'a, T, C> Cw721Contract<'a, T, C>
+where
+    T: Serialize + DeserializeOwned + Clone + MetaDataPersonalization,
+{
+    /// Walks up to `limit` entries of tokens, tokens_uri, image_uri and change_dynamics (in that
+    /// order) from `start_after`, cross-checking each against the others.
+    /// token_count is checked once the walk leaves the tokens, using the running count in the cursor.
+    /// entries written before uris were normalized are reported as stale, with the token missing
+    /// from its normalized key, so a repair moves them over
+    pub fn audit(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<AuditCursor>,
+        limit: usize,
+    ) -> StdResult<AuditPage> {
+        let mut cursor = start_after.unwrap_or_default();
+        let mut issues = vec![];
+        let mut budget = limit;
+
+        while budget > 0 {
+            let start = cursor.key.clone().map(Bound::exclusive);
+            let mut last_key = None;
+            let mut walked = 0;
+            match cursor.section {
+                AuditSection::Tokens => {
+                    for item in self
+                        .tokens
+                        .range(storage, start, None, Order::Ascending)
+                        .take(budget)
+                    {
+                        let (k, token) = item?;
+                        let token_id = String::from_utf8_lossy(&k).to_string();
+                        if let Some(token_uri) = &token.token_uri {
+                            match self.token_uri_holder(storage, &uri_key(token_uri))? {
+                                Some(held_by) if held_by == token_id => {}
+                                Some(held_by) => issues.push(IntegrityIssue::TokenUriConflict {
+                                    token_id: token_id.clone(),
+                                    token_uri: token_uri.clone(),
+                                    held_by,
+                                }),
+                                None => issues.push(IntegrityIssue::TokenUriMissing {
+                                    token_id: token_id.clone(),
+                                    token_uri: token_uri.clone(),
+                                }),
+                            }
+                        }
+                        if let Some(image) = token.extension.get_image_raw() {
+                            match self.image_holder(storage, &uri_key(&image))? {
+                                Some(held_by) if held_by == token_id => {}
+                                Some(held_by) => issues.push(IntegrityIssue::ImageUriConflict {
+                                    token_id: token_id.clone(),
+                                    image,
+                                    held_by,
+                                }),
+                                None => issues.push(IntegrityIssue::ImageUriMissing {
+                                    token_id: token_id.clone(),
+                                    image,
+                                }),
+                            }
+                        }
+                        if let Some(dynamics) = self.change_dynamics.may_load(storage, &token_id)? {
+                            if dynamics.token_id != token_id || dynamics.owner != token.owner {
+                                issues.push(IntegrityIssue::ChangeDynamicsMismatch {
+                                    token_id: token_id.clone(),
+                                });
+                            }
+                        }
+                        cursor.tokens_seen += 1;
+                        walked += 1;
+                        last_key = Some(token_id);
+                    }
+                }
+                AuditSection::TokensUri => {
+                    for item in self
+                        .tokens_uri
+                        .range(storage, start, None, Order::Ascending)
+                        .take(budget)
+                    {
+                        let (k, token_id) = item?;
+                        let token_uri = String::from_utf8_lossy(&k).to_string();
+                        let held = self
+                            .tokens
+                            .may_load(storage, &token_id)?
+                            .and_then(|t| t.token_uri)
+                            .map(|uri| uri_key(&uri) == token_uri)
+                            .unwrap_or(false);
+                        if !held {
+                            issues.push(IntegrityIssue::StaleTokenUri {
+                                token_uri: token_uri.clone(),
+                                token_id,
+                            });
+                        }
+                        walked += 1;
+                        last_key = Some(token_uri);
+                    }
+                }
+                AuditSection::ImageUri => {
+                    for item in self
+                        .image_uri
+                        .range(storage, start, None, Order::Ascending)
+                        .take(budget)
+                    {
+                        let (k, token_id) = item?;
+                        let image = String::from_utf8_lossy(&k).to_string();
+                        let held = self
+                            .tokens
+                            .may_load(storage, &token_id)?
+                            .and_then(|t| t.extension.get_image_raw())
+                            .map(|uri| uri_key(&uri) == image)
+                            .unwrap_or(false);
+                        if !held {
+                            issues.push(IntegrityIssue::StaleImageUri {
+                                image: image.clone(),
+                                token_id,
+                            });
+                        }
+                        walked += 1;
+                        last_key = Some(image);
+                    }
+                }
+                AuditSection::ChangeDynamics => {
+                    for item in self
+                        .change_dynamics
+                        .range(storage, start, None, Order::Ascending)
+                        .take(budget)
+                    {
+                        let (k, _) = item?;
+                        let token_id = String::from_utf8_lossy(&k).to_string();
+                        if self.tokens.may_load(storage, &token_id)?.is_none() {
+                            issues.push(IntegrityIssue::StaleChangeDynamics {
+                                token_id: token_id.clone(),
+                            });
+                        }
+                        walked += 1;
+                        last_key = Some(token_id);
+                    }
+                }
+            }
+            budget -= walked;
+            if budget > 0 {
+                // this section is done
+                if cursor.section == AuditSection::Tokens {
+                    let stored = self.token_count(storage)?;
+                    if stored != cursor.tokens_seen {
+                        issues.push(IntegrityIssue::TokenCount {
+                            stored,
+                            actual: cursor.tokens_seen,
+                        });
+                    }
+                }
+                match cursor.section.next() {
+                    Some(section) => {
+                        cursor.section = section;
+                        cursor.key = None;
+                    }
+                    None => return Ok(AuditPage { issues, next: None }),
+                }
+            } else {
+                cursor.key = last_key;
+            }
+        }
+        Ok(AuditPage {
+            issues,
+            next: Some(cursor),
+        })
+    }
+
+    /// Fixes what `audit` found, and returns how many were fixed. A missing index entry is only
+    /// written when no other token holds the value, entries left behind by burns and renames are
+    /// removed, and token_count is set to the count the audit carried. Conflicts are left alone
+    pub fn repair(&self, storage: &mut dyn Storage, issues: &[IntegrityIssue]) -> StdResult<u32> {
+        let mut repaired = 0;
+        for issue in issues {
+            match issue {
+                IntegrityIssue::TokenUriMissing {
+                    token_id,
+                    token_uri,
+                } => {
+                    // things may have moved on since the audit
+                    let key = uri_key(token_uri);
+                    if self.token_uri_holder(storage, &key)?.is_some() {
+                        continue;
+                    }
+                    self.tokens_uri.save(storage, &key, token_id)?
+                }
+                IntegrityIssue::ImageUriMissing { token_id, image } => {
+                    let key = uri_key(image);
+                    if self.image_holder(storage, &key)?.is_some() {
+                        continue;
+                    }
+                    self.image_uri.save(storage, &key, token_id)?
+                }
+                IntegrityIssue::TokenUriConflict { .. }
+                | IntegrityIssue::ImageUriConflict { .. } => continue,
+                IntegrityIssue::StaleTokenUri {
+                    token_uri,
+                    token_id,
+                } => {
+                    // the tokens pass may have already re-pointed it
+                    if self.tokens_uri.may_load(storage, token_uri)?.as_ref() == Some(token_id) {
+                        self.tokens_uri.remove(storage, token_uri)?;
+                    }
+                }
+                IntegrityIssue::StaleImageUri { image, token_id } => {
+                    if self.image_uri.may_load(storage, image)?.as_ref() == Some(token_id) {
+                        self.image_uri.remove(storage, image)?;
+                    }
+                }
+                IntegrityIssue::StaleChangeDynamics { token_id } => {
+                    self.change_dynamics.remove(storage, token_id)?
+                }
+                IntegrityIssue::ChangeDynamicsMismatch { token_id } => {
+                    let token = self.tokens.load(storage, token_id)?;
+                    let mut dynamics = self.change_dynamics.load(storage, token_id)?;
+                    dynamics.token_id = token_id.clone();
+                    dynamics.owner = token.owner;
+                    self.change_dynamics.save(storage, token_id, &dynamics)?;
+                }
+                IntegrityIssue::TokenCount { actual, .. } => {
+                    // counted a page at a time by the audit, recounting here could walk every token
+                    self.token_count.save(storage, actual)?
+                }
+            }
+            repaired += 1;
+        }
+        Ok(repaired)
+    }
+
+    /// the token tokens_uri has under key, if that token really has the uri
+    fn token_uri_holder(&self, storage: &dyn Storage, key: &str) -> StdResult<Option<String>> {
+        let token_id = match self.tokens_uri.may_load(storage, key)? {
+            Some(token_id) => token_id,
+            None => return Ok(None),
+        };
+        let held = self
+            .tokens
+            .may_load(storage, &token_id)?
+            .and_then(|t| t.token_uri)
+            .map(|uri| uri_key(&uri) == key)
+            .unwrap_or(false);
+        Ok(if held { Some(token_id) } else { None })
+    }
+
+    /// the token image_uri has under key, if that token really has the image
+    fn image_holder(&self, storage: &dyn Storage, key: &str) -> StdResult<Option<String>> {
+        let token_id = match self.image_uri.may_load(storage, key)? {
+            Some(token_id) => token_id,
+            None => return Ok(None),
+        };
+        let held = self
+            .tokens
+            .may_load(storage, &token_id)?
+            .and_then(|t| t.extension.get_image_raw())
+            .map(|uri| uri_key(&uri) == key)
+            .unwrap_or(false);
+        Ok(if held { Some(token_id) } else { None })
+    }
+}
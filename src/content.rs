@@ -0,0 +1,115 @@
+use crate::error::ContractError;
+use crate::state::{CharClass, ContentPolicy};
+
+/// names become token ids, so on top of the length they are held to the allowed character
+/// classes and can't use a reserved word. whitespace at either end, and without classes any
+/// control or format character, is turned away so two names can't look the same
+pub fn check_name(policy: &ContentPolicy, name: &str) -> Result<(), ContractError> {
+    if name.chars().count() > policy.max_name_length as usize {
+        return Err(ContractError::NameTooLong {
+            max_length: policy.max_name_length,
+        });
+    }
+    // this also catches names that are all whitespace
+    if name.trim() != name {
+        return Err(ContractError::NameWhitespace {});
+    }
+    for c in name.chars() {
+        let allowed = match &policy.name_chars {
+            Some(classes) => classes.iter().any(|class| class.contains(c)),
+            None => !c.is_control() && !is_format(c),
+        };
+        if !allowed {
+            return Err(ContractError::InvalidCharacter {
+                field: "name".to_string(),
+                character: c,
+            });
+        }
+    }
+    // reserved words can't be used as a word anywhere in the name
+    for word in name.split(|c: char| !c.is_alphanumeric()) {
+        if let Some(reserved) = policy
+            .reserved_words
+            .iter()
+            .find(|reserved| reserved.eq_ignore_ascii_case(word))
+        {
+            return Err(ContractError::ReservedWord {
+                word: reserved.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+pub fn check_description(policy: &ContentPolicy, description: &str) -> Result<(), ContractError> {
+    if description.chars().count() > policy.max_description_length as usize {
+        return Err(ContractError::DescriptionTooLong {
+            max_length: policy.max_description_length,
+        });
+    }
+    // line breaks are fine in a description
+    check_no_control("description", description, &['\n'])
+}
+
+pub fn check_status(policy: &ContentPolicy, status: &str) -> Result<(), ContractError> {
+    if status.chars().count() > policy.max_status_length as usize {
+        return Err(ContractError::StatusTooLong {
+            max_length: policy.max_status_length,
+        });
+    }
+    check_no_control("status", status, &[])
+}
+
+fn check_no_control(field: &str, value: &str, except: &[char]) -> Result<(), ContractError> {
+    match value
+        .chars()
+        .find(|c| c.is_control() && !except.contains(c))
+    {
+        Some(c) => Err(ContractError::InvalidCharacter {
+            field: field.to_string(),
+            character: c,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// the unicode Cf (format) characters: zero width spaces and joiners, direction marks and
+/// overrides, the soft hyphen, tags and the like
+fn is_format(c: char) -> bool {
+    matches!(
+        c,
+        '\u{ad}'
+            | '\u{600}'..='\u{605}'
+            | '\u{61c}'
+            | '\u{6dd}'
+            | '\u{70f}'
+            | '\u{890}'..='\u{891}'
+            | '\u{8e2}'
+            | '\u{180e}'
+            | '\u{200b}'..='\u{200f}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{206f}'
+            | '\u{feff}'
+            | '\u{fff9}'..='\u{fffb}'
+            | '\u{110bd}'
+            | '\u{110cd}'
+            | '\u{13430}'..='\u{1343f}'
+            | '\u{1bca0}'..='\u{1bca3}'
+            | '\u{1d173}'..='\u{1d17a}'
+            | '\u{e0001}'
+            | '\u{e0020}'..='\u{e007f}'
+    )
+}
+
+impl CharClass {
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            CharClass::Letters => c.is_alphabetic(),
+            CharClass::AsciiLetters => c.is_ascii_alphabetic(),
+            CharClass::Digits => c.is_ascii_digit(),
+            CharClass::Space => c == ' ',
+            CharClass::Punctuation => c.is_ascii_punctuation(),
+        }
+    }
+}
@@ -1,22 +1,46 @@
 #![cfg(test)]
-
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+#![allow(
+    clippy::assertions_on_constants,
+    clippy::bool_assert_comparison,
+    clippy::single_match,
+    noop_method_call
+)]
+
+use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{
-    from_binary, to_binary, Coin, CosmosMsg, Decimal, DepsMut, Empty, Response, StdError, WasmMsg,
+    from_binary, to_binary, BankMsg, Binary, Coin, ContractResult, CosmosMsg, Decimal, Deps,
+    DepsMut, Empty, Reply, ReplyOn, Response, StdError, SubMsg, SubMsgExecutionResponse, Uint128,
+    WasmMsg,
 };
+use sha2::{Digest, Sha256};
 use std::str::FromStr;
 
+use cw0::PaymentError;
 use cw721::{
     ApprovedForAllResponse, ContractInfoResponse, Cw721Query, Cw721ReceiveMsg, Expiration,
-    NftInfoResponse, OwnerOfResponse,
+    NftInfoResponse, OwnerOfResponse, TokensResponse,
 };
+use cw_storage_plus::{Map, U64Key};
 
-use crate::extension::{MetaDataPersonalization, Metadata, Trait};
-use crate::msg::BuyMsg;
-use crate::state::{NftListing, NftTraitSummary};
+use crate::extension::{
+    MetaDataPersonalization, Metadata, MetadataPatch, Trait, UriField, UriPrefixes,
+};
+use crate::mock_querier::mock_dependencies;
+use crate::msg::{
+    AuditIntegrityResponse, BlockedNamesResponse, BuyMsg, IntegrityIssue,
+    MetadataDelegatesResponse, OperatorsPageResponse, Order, PayeeMsg, PayeesResponse,
+    SendHistoryResponse, SerialTokensResponse, StakeInfoResponse, StakedTokensResponse,
+    TokenRarityResponse, TokenUrisResponse, TokensPageResponse, TraitCountsResponse,
+    UserOfResponse, WithdrawalsResponse,
+};
+use crate::state::{
+    ChangeDynamics, ChangeKind, ChangePricing, CharClass, ContentPolicy, FieldAccess,
+    MetadataFieldAccess, MetadataFreeze, NftListing, NftTraitSummary, Provenance, SendErrorPolicy,
+    SerialToken, TokenInfo, TokenUri, UriPolicy,
+};
 use crate::{
-    BuyExtension, ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, MintMsg,
-    QueryMsg,
+    BuyExtension, ContractError, Cw721Contract, ExecuteMsg, Extension, InstantiateMsg, MigrateMsg,
+    MintMsg, QueryMsg, TokensWithInfoResponse,
 };
 
 const MINTER: &str = "merlin";
@@ -36,6 +60,7 @@ fn setup_contract(deps: DepsMut<'_>) -> Cw721Contract<'static, Extension, Empty>
         change_amount: 1_000_000u64,
         change_multiplier: 2u64,
         max_issuance: 3u64,
+        provenance_hash: None,
     };
     let info = mock_info("creator", &[]);
     let res = contract.instantiate(deps, mock_env(), info, msg).unwrap();
@@ -43,6 +68,30 @@ fn setup_contract(deps: DepsMut<'_>) -> Cw721Contract<'static, Extension, Empty>
     contract
 }
 
+/// mints a token with a unique token_uri & image derived from the token_id
+fn mint_token(
+    deps: DepsMut<'_>,
+    contract: &Cw721Contract<'static, Extension, Empty>,
+    token_id: &str,
+    owner: &str,
+) {
+    let token_uri = format!("https://www.merriam-webster.com/dictionary/{}", token_id);
+    let extension = Metadata {
+        token_uri: token_uri.clone(),
+        image: Some(format!("Qm{}", token_id)),
+        ..Metadata::default()
+    };
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+        token_uri: Some(token_uri),
+        extension,
+    });
+    contract
+        .execute(deps, mock_env(), mock_info(MINTER, &[]), mint_msg)
+        .unwrap();
+}
+
 #[test]
 fn proper_instantiation() {
     let mut deps = mock_dependencies(&[]);
@@ -57,6 +106,7 @@ fn proper_instantiation() {
         change_amount: 1_000_000u64,
         change_multiplier: 10,
         max_issuance: 3u64,
+        provenance_hash: None,
     };
     let info = mock_info("creator", &[]);
 
@@ -379,6 +429,7 @@ fn sending_nft() {
         contract: target.clone(),
         token_id: token_id.clone(),
         msg: msg.clone(),
+        reply_on: None,
     };
 
     let random = mock_info("random", &[]);
@@ -425,363 +476,798 @@ fn sending_nft() {
 }
 
 #[test]
-fn approving_revoking() {
+fn sending_nft_with_reply() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
 
-    // Mint a token
-    let token_id = "grow".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
-    let extension = Metadata {
-        token_uri: token_uri.clone(),
-        image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
-        image_data: None,
-        external_url: None,
-        description: None,
-        name: None,
-        attributes: None,
-        background_color: None,
-        animation_url: None,
-        youtube_url: None,
-        current_status: None,
-    };
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id.clone(),
-        owner: String::from("demeter"),
-        token_uri: Some(token_uri),
-        extension: extension.clone(),
-    });
-
-    let minter = mock_info(MINTER, &[]);
-    contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
-        .unwrap();
+    mint_token(deps.as_mut(), &contract, "melt", "venus");
+    mint_token(deps.as_mut(), &contract, "freeze", "venus");
 
-    // Give random transferring power
-    let approve_msg = ExecuteMsg::Approve {
-        spender: String::from("random"),
-        token_id: token_id.clone(),
-        expires: None,
+    let msg = to_binary("You now have the melting power").unwrap();
+    let target = String::from("another_contract");
+    let send_msg = ExecuteMsg::SendNft {
+        contract: target.clone(),
+        token_id: "melt".to_string(),
+        msg: msg.clone(),
+        reply_on: Some(ReplyOn::Always),
     };
-    let owner = mock_info("demeter", &[]);
+    let venus = mock_info("venus", &[]);
     let res = contract
-        .execute(deps.as_mut(), mock_env(), owner, approve_msg)
+        .execute(deps.as_mut(), mock_env(), venus, send_msg)
         .unwrap();
+    assert_eq!(1, res.messages.len());
+    let sub_msg = &res.messages[0];
+    assert_eq!(ReplyOn::Always, sub_msg.reply_on);
+    let payload = Cw721ReceiveMsg {
+        sender: String::from("venus"),
+        token_id: "melt".to_string(),
+        msg: msg.clone(),
+    };
     assert_eq!(
-        res,
-        Response::new()
-            .add_attribute("action", "approve")
-            .add_attribute("sender", "demeter")
-            .add_attribute("spender", "random")
-            .add_attribute("token_id", token_id.clone())
+        payload.into_cosmos_msg(target.clone()).unwrap(),
+        sub_msg.msg
     );
 
-    // random can now transfer
-    let random = mock_info("random", &[]);
-    let transfer_msg = ExecuteMsg::TransferNft {
-        recipient: String::from("person"),
-        token_id: token_id.clone(),
-    };
-    contract
-        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
-        .unwrap();
-
-    // Approvals are removed / cleared
-    let query_msg = QueryMsg::OwnerOf {
-        token_id: token_id.clone(),
-        include_expired: None,
+    // receiver answered, and the data is kept
+    let reply = Reply {
+        id: sub_msg.id,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: Some(Binary::from(b"listed".to_vec())),
+        }),
     };
-    let res: OwnerOfResponse = from_binary(
+    contract.reply(deps.as_mut(), mock_env(), reply).unwrap();
+    let history: SendHistoryResponse = from_binary(
         &contract
-            .query(deps.as_ref(), mock_env(), query_msg.clone())
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SendHistory {
+                    token_id: "melt".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap(),
     )
     .unwrap();
+    assert_eq!(1, history.history.len());
+    assert!(history.history[0].success);
     assert_eq!(
-        res,
-        OwnerOfResponse {
-            owner: String::from("person"),
-            approvals: vec![],
-        }
+        Some(Binary::from(b"listed".to_vec())),
+        history.history[0].data
     );
 
-    // Approve, revoke, and check for empty, to test revoke
-    let approve_msg = ExecuteMsg::Approve {
-        spender: String::from("random"),
-        token_id: token_id.clone(),
-        expires: None,
+    // the same reply can't be replayed
+    let reply = Reply {
+        id: sub_msg.id,
+        result: ContractResult::Err("again".to_string()),
     };
-    let owner = mock_info("person", &[]);
-    contract
-        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+    match contract.reply(deps.as_mut(), mock_env(), reply) {
+        Err(ContractError::UnknownReply { .. }) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+
+    // by default a failing receiver reverts everything
+    let send_msg = ExecuteMsg::SendNft {
+        contract: target.clone(),
+        token_id: "freeze".to_string(),
+        msg,
+        reply_on: Some(ReplyOn::Error),
+    };
+    let venus = mock_info("venus", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), venus.clone(), send_msg.clone())
         .unwrap();
+    let reply = Reply {
+        id: res.messages[0].id,
+        result: ContractResult::Err("no thanks".to_string()),
+    };
+    match contract.reply(deps.as_mut(), mock_env(), reply) {
+        Err(ContractError::SendFailed { error }) => assert_eq!("no thanks", error),
+        x => assert!(false, "Unexpected {:?}", x),
+    }
 
-    let revoke_msg = ExecuteMsg::Revoke {
-        spender: String::from("random"),
-        token_id,
+    // only the minter can change the policy
+    let policy_msg = ExecuteMsg::SetSendErrorPolicy {
+        policy: SendErrorPolicy::KeepTransfer,
     };
+    match contract.execute(deps.as_mut(), mock_env(), venus, policy_msg.clone()) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let minter = mock_info(MINTER, &[]);
     contract
-        .execute(deps.as_mut(), mock_env(), owner, revoke_msg)
+        .execute(deps.as_mut(), mock_env(), minter, policy_msg)
         .unwrap();
 
-    // Approvals are now removed / cleared
-    let res: OwnerOfResponse = from_binary(
+    // now the transfer is kept, and the error logged
+    let another = mock_info("another_contract", &[]);
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            another,
+            ExecuteMsg::SendNft {
+                contract: "third_contract".to_string(),
+                token_id: "freeze".to_string(),
+                msg: to_binary("pass it on").unwrap(),
+                reply_on: Some(ReplyOn::Error),
+            },
+        )
+        .unwrap();
+    let reply = Reply {
+        id: res.messages[0].id,
+        result: ContractResult::Err("no thanks".to_string()),
+    };
+    contract.reply(deps.as_mut(), mock_env(), reply).unwrap();
+    let owner = contract
+        .owner_of(deps.as_ref(), mock_env(), "freeze".to_string(), false)
+        .unwrap();
+    assert_eq!("third_contract", owner.owner);
+    let history: SendHistoryResponse = from_binary(
         &contract
-            .query(deps.as_ref(), mock_env(), query_msg)
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SendHistory {
+                    token_id: "freeze".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
             .unwrap(),
     )
     .unwrap();
-    assert_eq!(
-        res,
-        OwnerOfResponse {
-            owner: String::from("person"),
-            approvals: vec![],
-        }
-    );
+    // the reverted send was never recorded
+    assert_eq!(1, history.history.len());
+    let last = history.history.last().unwrap();
+    assert!(!last.success);
+    assert_eq!(Some("no thanks".to_string()), last.error);
+
+    // sends in flight are told apart by their reply id. replying only on success still
+    // reverts on failure, and replying only on error still records a success
+    let send = |deps: DepsMut, owner: &str, token_id: &str, reply_on: ReplyOn| -> u64 {
+        contract
+            .execute(
+                deps,
+                mock_env(),
+                mock_info(owner, &[]),
+                ExecuteMsg::SendNft {
+                    contract: "venus".to_string(),
+                    token_id: token_id.to_string(),
+                    msg: to_binary("pass it on").unwrap(),
+                    reply_on: Some(reply_on),
+                },
+            )
+            .unwrap()
+            .messages[0]
+            .id
+    };
+    let melt_id = send(deps.as_mut(), &target, "melt", ReplyOn::Success);
+    let freeze_id = send(deps.as_mut(), "third_contract", "freeze", ReplyOn::Error);
+    let reply = Reply {
+        id: freeze_id,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    contract.reply(deps.as_mut(), mock_env(), reply).unwrap();
+    let record = contract
+        .send_history
+        .load(&deps.storage, ("freeze", freeze_id.into()))
+        .unwrap();
+    assert!(record.success);
+    assert_eq!(ReplyOn::Error, record.reply_on);
+    let reply = Reply {
+        id: melt_id,
+        result: ContractResult::Err("no thanks".to_string()),
+    };
+    match contract.reply(deps.as_mut(), mock_env(), reply) {
+        Err(ContractError::SendFailed { error }) => assert_eq!("no thanks", error),
+        x => panic!("Unexpected {:?}", x),
+    }
 }
 
 #[test]
-fn approving_all_revoking_all() {
+fn locking() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "badge", "venus");
+    mint_token(deps.as_mut(), &contract, "peep", "venus");
 
-    // Mint a couple tokens (from the same owner)
-    let token_id1 = "grow1".to_string();
-    let token_uri1 = "https://www.merriam-webster.com/dictionary/grow1".to_string();
-
-    let token_id2 = "grow2".to_string();
-    let token_uri2 = "https://www.merriam-webster.com/dictionary/grow2".to_string();
-    let mut extension = Metadata {
-        token_uri: token_uri1.clone(),
-        image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
-        image_data: None,
-        external_url: None,
-        description: None,
-        name: None,
-        attributes: None,
-        background_color: None,
-        animation_url: None,
-        youtube_url: None,
-        current_status: None,
+    let venus = mock_info("venus", &[]);
+    let random = mock_info("random", &[]);
+    let minter = mock_info(MINTER, &[]);
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: "badge".to_string(),
+    };
+    let mut env = mock_env();
+    let lock_msg = ExecuteMsg::LockToken {
+        token_id: "badge".to_string(),
+        expires: Some(Expiration::AtHeight(env.block.height + 100)),
     };
-    let mint_msg1 = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id1.clone(),
-        owner: String::from("demeter"),
-        token_uri: Some(token_uri1),
-        extension: extension.clone(),
-    });
-    extension.image = Some("QmReTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
 
-    let minter = mock_info(MINTER, &[]);
+    // random cannot lock
+    match contract.execute(deps.as_mut(), env.clone(), random.clone(), lock_msg.clone()) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
     contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg1)
+        .execute(deps.as_mut(), env.clone(), venus.clone(), lock_msg)
         .unwrap();
 
-    let mint_msg2 = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id2.clone(),
-        owner: String::from("demeter"),
-        token_uri: Some(token_uri2),
-        extension: extension.clone(),
-    });
+    // no transfers, no approvals
+    match contract.execute(
+        deps.as_mut(),
+        env.clone(),
+        venus.clone(),
+        transfer_msg.clone(),
+    ) {
+        Err(ContractError::Locked {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let approve_msg = ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: "badge".to_string(),
+        expires: None,
+    };
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), approve_msg) {
+        Err(ContractError::Locked {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
 
+    // the owner has to wait it out
+    let unlock_msg = ExecuteMsg::UnlockToken {
+        token_id: "badge".to_string(),
+    };
+    match contract.execute(
+        deps.as_mut(),
+        env.clone(),
+        venus.clone(),
+        unlock_msg.clone(),
+    ) {
+        Err(ContractError::Locked {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    // or to cut it short
+    let shorter_msg = ExecuteMsg::LockToken {
+        token_id: "badge".to_string(),
+        expires: Some(Expiration::AtHeight(env.block.height + 1)),
+    };
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), shorter_msg) {
+        Err(ContractError::Locked {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    // or to switch a height lock to a time
+    let time_msg = ExecuteMsg::LockToken {
+        token_id: "badge".to_string(),
+        expires: Some(Expiration::AtTime(env.block.time.plus_seconds(86_400))),
+    };
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), time_msg) {
+        Err(ContractError::LockExpiryMismatch {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    env.block.height += 100;
     contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg2)
-        .unwrap();
-
-    // paginate the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, Some(1)).unwrap();
-    assert_eq!(1, tokens.tokens.len());
-    assert_eq!(vec![token_id1.clone()], tokens.tokens);
-    let tokens = contract
-        .all_tokens(deps.as_ref(), Some(token_id1.clone()), Some(3))
+        .execute(deps.as_mut(), env.clone(), venus.clone(), transfer_msg)
         .unwrap();
-    assert_eq!(1, tokens.tokens.len());
-    assert_eq!(vec![token_id2.clone()], tokens.tokens);
 
-    // demeter gives random full (operator) power over her tokens
-    let approve_all_msg = ExecuteMsg::ApproveAll {
-        operator: String::from("random"),
+    // the contract owner can lock forever, and unlock whenever. the token owner can't, as
+    // only the contract owner could lift it
+    let lock_msg = ExecuteMsg::LockToken {
+        token_id: "peep".to_string(),
         expires: None,
     };
-    let owner = mock_info("demeter", &[]);
-    let res = contract
-        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
-        .unwrap();
-    assert_eq!(
-        res,
-        Response::new()
-            .add_attribute("action", "approve_all")
-            .add_attribute("sender", "demeter")
-            .add_attribute("operator", "random")
-    );
-
-    // random can now transfer
-    let random = mock_info("random", &[]);
-    let transfer_msg = ExecuteMsg::TransferNft {
-        recipient: String::from("person"),
-        token_id: token_id1,
-    };
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), lock_msg.clone()) {
+        Err(ContractError::LockNeedsExpiry {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
     contract
-        .execute(deps.as_mut(), mock_env(), random.clone(), transfer_msg)
+        .execute(deps.as_mut(), env.clone(), minter.clone(), lock_msg)
         .unwrap();
-
-    // random can now send
-    let inner_msg = WasmMsg::Execute {
-        contract_addr: "another_contract".into(),
-        msg: to_binary("You now also have the growing power").unwrap(),
-        funds: vec![],
+    let lock: Option<Expiration> = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::TokenLock {
+                    token_id: "peep".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(Some(Expiration::Never {}), lock);
+    // which the owner can't swap for a lock that ends
+    let replace_msg = ExecuteMsg::LockToken {
+        token_id: "peep".to_string(),
+        expires: Some(Expiration::AtHeight(env.block.height + 1_000)),
     };
-    let msg: CosmosMsg = CosmosMsg::Wasm(inner_msg);
-
-    let send_msg = ExecuteMsg::SendNft {
-        contract: String::from("another_contract"),
-        token_id: token_id2,
-        msg: to_binary(&msg).unwrap(),
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), replace_msg) {
+        Err(ContractError::Locked {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    let unlock_msg = ExecuteMsg::UnlockToken {
+        token_id: "peep".to_string(),
     };
     contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg)
+        .execute(deps.as_mut(), env.clone(), minter.clone(), unlock_msg)
         .unwrap();
 
-    // Approve_all, revoke_all, and check for empty, to test revoke_all
-    let approve_all_msg = ExecuteMsg::ApproveAll {
-        operator: String::from("operator"),
-        expires: None,
-    };
-    // person is now the owner of the tokens
-    let owner = mock_info("person", &[]);
+    // soulbound collections can't move at all
     contract
-        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter,
+            ExecuteMsg::SetSoulbound { soulbound: true },
+        )
         .unwrap();
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: "peep".to_string(),
+    };
+    match contract.execute(deps.as_mut(), env, venus, transfer_msg) {
+        Err(ContractError::Locked {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+}
 
-    let res = contract
-        .all_approvals(
-            deps.as_ref(),
-            mock_env(),
-            String::from("person"),
-            true,
-            None,
-            None,
+#[test]
+fn token_users() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "peep", "venus");
+
+    let venus = mock_info("venus", &[]);
+    let renter = mock_info("renter", &[]);
+    let mut env = mock_env();
+    let set_user_msg = ExecuteMsg::SetUser {
+        token_id: "peep".to_string(),
+        user: Some("renter".to_string()),
+        expires: Some(Expiration::AtHeight(env.block.height + 10)),
+    };
+    // the renter can't make themselves the user
+    match contract.execute(
+        deps.as_mut(),
+        env.clone(),
+        renter.clone(),
+        set_user_msg.clone(),
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            venus.clone(),
+            set_user_msg.clone(),
         )
         .unwrap();
-    assert_eq!(
-        res,
-        ApprovedForAllResponse {
-            operators: vec![cw721::Approval {
-                spender: String::from("operator"),
-                expires: Expiration::Never {}
-            }]
-        }
-    );
+    let user_of = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, env: &cosmwasm_std::Env| {
+        let res: UserOfResponse = from_binary(
+            &contract
+                .query(
+                    deps.as_ref(),
+                    env.clone(),
+                    QueryMsg::UserOf {
+                        token_id: "peep".to_string(),
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        res.user
+    };
+    assert_eq!(Some("renter".to_string()), user_of(&deps, &env));
 
-    // second approval
-    let buddy_expires = Expiration::AtHeight(1234567);
-    let approve_all_msg = ExecuteMsg::ApproveAll {
-        operator: String::from("buddy"),
-        expires: Some(buddy_expires),
+    // the user can set the status, but can't move the token
+    let status_msg = ExecuteMsg::SetTokenStatus {
+        status: "on loan".to_string(),
+        token_id: "peep".to_string(),
     };
-    let owner = mock_info("person", &[]);
     contract
-        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_all_msg)
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            renter.clone(),
+            status_msg.clone(),
+        )
         .unwrap();
+    let info = contract
+        .nft_info(deps.as_ref(), "peep".to_string())
+        .unwrap();
+    assert_eq!(Some("on loan".to_string()), info.extension.current_status);
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("renter"),
+        token_id: "peep".to_string(),
+    };
+    match contract.execute(deps.as_mut(), env.clone(), renter.clone(), transfer_msg) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
 
-    // and paginate queries
-    let res = contract
-        .all_approvals(
-            deps.as_ref(),
+    // the grant lapses by itself
+    env.block.height += 10;
+    assert_eq!(None, user_of(&deps, &env));
+    match contract.execute(
+        deps.as_mut(),
+        env.clone(),
+        renter.clone(),
+        status_msg.clone(),
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+
+    // and is removed when the token changes hands
+    let set_user_msg = ExecuteMsg::SetUser {
+        token_id: "peep".to_string(),
+        user: Some("renter".to_string()),
+        expires: None,
+    };
+    contract
+        .execute(deps.as_mut(), env.clone(), venus.clone(), set_user_msg)
+        .unwrap();
+    assert_eq!(Some("renter".to_string()), user_of(&deps, &env));
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: "peep".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), env.clone(), venus, transfer_msg)
+        .unwrap();
+    assert_eq!(None, user_of(&deps, &env));
+    match contract.execute(deps.as_mut(), env, renter, status_msg) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+}
+
+#[test]
+fn metadata_delegates() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "peep", "venus");
+
+    let venus = mock_info("venus", &[]);
+    let market = mock_info("market", &[]);
+    let artist = mock_info("artist", &[]);
+    let status_msg = ExecuteMsg::SetTokenStatus {
+        status: "for sale".to_string(),
+        token_id: "peep".to_string(),
+    };
+
+    // an operator can move the token, but can't touch the metadata
+    contract
+        .execute(
+            deps.as_mut(),
             mock_env(),
-            String::from("person"),
-            true,
-            None,
-            Some(1),
+            venus.clone(),
+            ExecuteMsg::ApproveAll {
+                operator: "market".to_string(),
+                expires: None,
+            },
         )
         .unwrap();
-    assert_eq!(
-        res,
-        ApprovedForAllResponse {
-            operators: vec![cw721::Approval {
-                spender: String::from("buddy"),
-                expires: buddy_expires,
-            }]
-        }
-    );
-    let res = contract
-        .all_approvals(
-            deps.as_ref(),
+    match contract.execute(deps.as_mut(), mock_env(), market, status_msg.clone()) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+
+    // a token delegate can, but only until the token is transferred
+    let add_msg = ExecuteMsg::AddMetadataDelegate {
+        delegate: "artist".to_string(),
+        token_id: Some("peep".to_string()),
+    };
+    match contract.execute(deps.as_mut(), mock_env(), artist.clone(), add_msg.clone()) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    contract
+        .execute(deps.as_mut(), mock_env(), venus.clone(), add_msg)
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
             mock_env(),
-            String::from("person"),
-            true,
-            Some(String::from("buddy")),
-            Some(2),
+            artist.clone(),
+            status_msg.clone(),
         )
         .unwrap();
-    assert_eq!(
-        res,
-        ApprovedForAllResponse {
-            operators: vec![cw721::Approval {
-                spender: String::from("operator"),
-                expires: Expiration::Never {}
-            }]
-        }
-    );
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("artist"),
+        token_id: "peep".to_string(),
+    };
+    match contract.execute(deps.as_mut(), mock_env(), artist.clone(), transfer_msg) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let delegates_query = QueryMsg::MetadataDelegates {
+        token_id: "peep".to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let delegates: MetadataDelegatesResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), delegates_query.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(vec!["artist".to_string()], delegates.token_delegates);
+    assert!(delegates.owner_delegates.is_empty());
 
-    let revoke_all_msg = ExecuteMsg::RevokeAll {
-        operator: String::from("operator"),
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: "peep".to_string(),
     };
     contract
-        .execute(deps.as_mut(), mock_env(), owner, revoke_all_msg)
+        .execute(deps.as_mut(), mock_env(), venus, transfer_msg)
         .unwrap();
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        artist.clone(),
+        status_msg.clone(),
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
 
-    // Approvals are removed / cleared without affecting others
-    let res = contract
-        .all_approvals(
-            deps.as_ref(),
+    // owner delegates cover all the owner's tokens
+    let random = mock_info("random", &[]);
+    contract
+        .execute(
+            deps.as_mut(),
             mock_env(),
-            String::from("person"),
-            false,
-            None,
-            None,
+            random.clone(),
+            ExecuteMsg::AddMetadataDelegate {
+                delegate: "artist".to_string(),
+                token_id: None,
+            },
         )
         .unwrap();
-    assert_eq!(
-        res,
-        ApprovedForAllResponse {
-            operators: vec![cw721::Approval {
-                spender: String::from("buddy"),
-                expires: buddy_expires,
-            }]
-        }
-    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            artist.clone(),
+            status_msg.clone(),
+        )
+        .unwrap();
+    let delegates: MetadataDelegatesResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), delegates_query)
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(delegates.token_delegates.is_empty());
+    assert_eq!(vec!["artist".to_string()], delegates.owner_delegates);
 
-    // ensure the filter works (nothing should be here
-    let mut late_env = mock_env();
-    late_env.block.height = 1234568; //expired
-    let res = contract
-        .all_approvals(
-            deps.as_ref(),
-            late_env,
-            String::from("person"),
-            false,
-            None,
-            None,
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            random,
+            ExecuteMsg::RemoveMetadataDelegate {
+                delegate: "artist".to_string(),
+                token_id: None,
+            },
         )
         .unwrap();
-    assert_eq!(0, res.operators.len());
+    match contract.execute(deps.as_mut(), mock_env(), artist, status_msg) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
 }
 
 #[test]
-fn query_tokens_by_owner() {
+fn staking() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
-    let minter = mock_info(MINTER, &[]);
+    mint_token(deps.as_mut(), &contract, "peep", "venus");
+    mint_token(deps.as_mut(), &contract, "other", "venus");
+
+    let venus = mock_info("venus", &[]);
+    let mut env = mock_env();
+    let stake_msg = ExecuteMsg::Stake {
+        token_id: "peep".to_string(),
+        duration: 1_000,
+    };
+    match contract.execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("random", &[]),
+        stake_msg.clone(),
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    let forever_msg = ExecuteMsg::Stake {
+        token_id: "peep".to_string(),
+        duration: u64::MAX,
+    };
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), forever_msg) {
+        Err(ContractError::StakeTooLong {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    contract
+        .execute(deps.as_mut(), env.clone(), venus.clone(), stake_msg.clone())
+        .unwrap();
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), stake_msg) {
+        Err(ContractError::Staked {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
 
-    // Mint a couple tokens (from the same owner)
-    let token_id1 = "grow1".to_string();
-    let demeter = String::from("Demeter");
-    let token_id2 = "grow2".to_string();
-    let ceres = String::from("Ceres");
-    let token_id3 = "sing".to_string();
-    let mut extension = Metadata {
-        token_uri: token_id1.clone(),
+    // staked tokens stay put
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: "peep".to_string(),
+    };
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), transfer_msg) {
+        Err(ContractError::Staked {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let burn_msg = ExecuteMsg::Burn {
+        token_id: "peep".to_string(),
+    };
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), burn_msg) {
+        Err(ContractError::Staked {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+
+    let staked: StakedTokensResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakedTokens {
+                    owner: "venus".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(1, staked.stakes.len());
+    assert_eq!("peep", staked.stakes[0].token_id);
+    assert_eq!(0, staked.staked_time);
+    assert_eq!(None, staked.next);
+
+    // only max_limit of the owner's tokens are looked at a page, staked or not
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetQueryLimits {
+                default_limit: 1,
+                max_limit: 1,
+            },
+        )
+        .unwrap();
+    let staked_page = |deps: Deps, start_after: Option<String>| -> StakedTokensResponse {
+        from_binary(
+            &contract
+                .query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::StakedTokens {
+                        owner: "venus".to_string(),
+                        start_after,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap()
+    };
+    let staked = staked_page(deps.as_ref(), None);
+    assert!(staked.stakes.is_empty());
+    assert_eq!(Some("other".to_string()), staked.next);
+    let staked = staked_page(deps.as_ref(), staked.next);
+    assert_eq!("peep", staked.stakes[0].token_id);
+    assert_eq!(None, staked.next);
+
+    // still in lock-up
+    env.block.time = env.block.time.plus_seconds(600);
+    let unstake_msg = ExecuteMsg::Unstake {
+        token_id: "peep".to_string(),
+    };
+    match contract.execute(
+        deps.as_mut(),
+        env.clone(),
+        venus.clone(),
+        unstake_msg.clone(),
+    ) {
+        Err(ContractError::StakeLocked { .. }) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let info: StakeInfoResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakeInfo {
+                    token_id: "peep".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(info.stake.is_some());
+    assert_eq!(600, info.staked_time);
+
+    env.block.time = env.block.time.plus_seconds(900);
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            venus.clone(),
+            unstake_msg.clone(),
+        )
+        .unwrap();
+    match contract.execute(deps.as_mut(), env.clone(), venus.clone(), unstake_msg) {
+        Err(ContractError::NotStaked {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+
+    let info: StakeInfoResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakeInfo {
+                    token_id: "peep".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(None, info.stake);
+    assert_eq!(1_500, info.staked_time);
+    let staked: StakedTokensResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::StakedTokens {
+                    owner: "venus".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(0, staked.stakes.len());
+    assert_eq!(1_500, staked.staked_time);
+
+    // and it can move again
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: "peep".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), env, venus, transfer_msg)
+        .unwrap();
+}
+
+#[test]
+fn approving_revoking() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a token
+    let token_id = "grow".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
+    let extension = Metadata {
+        token_uri: token_uri.clone(),
         image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
         image_data: None,
         external_url: None,
@@ -794,85 +1280,113 @@ fn query_tokens_by_owner() {
         current_status: None,
     };
     let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id1.clone(),
-        owner: demeter.clone(),
-        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv1".to_string()),
+        token_id: token_id.clone(),
+        owner: String::from("demeter"),
+        token_uri: Some(token_uri),
         extension: extension.clone(),
     });
-    contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
-        .unwrap();
 
-    extension.image = Some("QmRdTSx4v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id2.clone(),
-        owner: ceres.clone(),
-        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2".to_string()),
-        extension: extension.clone(),
-    });
-    contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
-        .unwrap();
-    extension.image = Some("QmRdTSx5v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
-
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id3.clone(),
-        owner: demeter.clone(),
-        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv3".to_string()),
-        extension: extension.clone(),
-    });
+    let minter = mock_info(MINTER, &[]);
     contract
         .execute(deps.as_mut(), mock_env(), minter, mint_msg)
         .unwrap();
 
-    // get all tokens in order:
-    let expected = vec![token_id1.clone(), token_id2.clone(), token_id3.clone()];
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
-    assert_eq!(&expected, &tokens.tokens);
-    // paginate
-    let tokens = contract.all_tokens(deps.as_ref(), None, Some(2)).unwrap();
-    assert_eq!(&expected[..2], &tokens.tokens[..]);
-    let tokens = contract
-        .all_tokens(deps.as_ref(), Some(expected[1].clone()), None)
+    // Give random transferring power
+    let approve_msg = ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: token_id.clone(),
+        expires: None,
+    };
+    let owner = mock_info("demeter", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_msg)
         .unwrap();
-    assert_eq!(&expected[2..], &tokens.tokens[..]);
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", "demeter")
+            .add_attribute("spender", "random")
+            .add_attribute("token_id", token_id.clone())
+    );
 
-    // get by owner
-    let by_ceres = vec![token_id2];
-    let by_demeter = vec![token_id1, token_id3];
-    // all tokens by owner
-    let tokens = contract
-        .tokens(deps.as_ref(), demeter.clone(), None, None)
+    // random can now transfer
+    let random = mock_info("random", &[]);
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("person"),
+        token_id: token_id.clone(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
         .unwrap();
-    assert_eq!(&by_demeter, &tokens.tokens);
-    let tokens = contract.tokens(deps.as_ref(), ceres, None, None).unwrap();
-    assert_eq!(&by_ceres, &tokens.tokens);
 
-    // paginate for demeter
-    let tokens = contract
-        .tokens(deps.as_ref(), demeter.clone(), None, Some(1))
+    // Approvals are removed / cleared
+    let query_msg = QueryMsg::OwnerOf {
+        token_id: token_id.clone(),
+        include_expired: None,
+    };
+    let res: OwnerOfResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        OwnerOfResponse {
+            owner: String::from("person"),
+            approvals: vec![],
+        }
+    );
+
+    // Approve, revoke, and check for empty, to test revoke
+    let approve_msg = ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: token_id.clone(),
+        expires: None,
+    };
+    let owner = mock_info("person", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
         .unwrap();
-    assert_eq!(&by_demeter[..1], &tokens.tokens[..]);
-    let tokens = contract
-        .tokens(deps.as_ref(), demeter, Some(by_demeter[0].clone()), Some(3))
+
+    let revoke_msg = ExecuteMsg::Revoke {
+        spender: String::from("random"),
+        token_id,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, revoke_msg)
         .unwrap();
-    assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
+
+    // Approvals are now removed / cleared
+    let res: OwnerOfResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        OwnerOfResponse {
+            owner: String::from("person"),
+            approvals: vec![],
+        }
+    );
 }
 
 #[test]
-fn query_tokens_by_image() {
+fn approving_all_revoking_all() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
-    let minter = mock_info(MINTER, &[]);
 
     // Mint a couple tokens (from the same owner)
     let token_id1 = "grow1".to_string();
-    let demeter = String::from("Demeter");
+    let token_uri1 = "https://www.merriam-webster.com/dictionary/grow1".to_string();
+
     let token_id2 = "grow2".to_string();
-    let ceres = String::from("Ceres");
-    let token_id3 = "sing".to_string();
+    let token_uri2 = "https://www.merriam-webster.com/dictionary/grow2".to_string();
     let mut extension = Metadata {
-        token_uri: token_id1.clone(),
+        token_uri: token_uri1.clone(),
         image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
         image_data: None,
         external_url: None,
@@ -884,375 +1398,381 @@ fn query_tokens_by_image() {
         youtube_url: None,
         current_status: None,
     };
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+    let mint_msg1 = ExecuteMsg::Mint(MintMsg::<Extension> {
         token_id: token_id1.clone(),
-        owner: demeter.clone(),
-        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv1".to_string()),
+        owner: String::from("demeter"),
+        token_uri: Some(token_uri1),
         extension: extension.clone(),
     });
+    extension.image = Some("QmReTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
+
+    let minter = mock_info(MINTER, &[]);
     contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg1)
         .unwrap();
 
-    extension.image = Some("QmRdTSx4v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
-    extension.token_uri = "QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2".to_string();
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+    let mint_msg2 = ExecuteMsg::Mint(MintMsg::<Extension> {
         token_id: token_id2.clone(),
-        owner: ceres.clone(),
-        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2".to_string()),
+        owner: String::from("demeter"),
+        token_uri: Some(token_uri2),
         extension: extension.clone(),
     });
+
     contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg2)
         .unwrap();
-    extension.image = Some("QmRdTSx5v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
-    extension.token_uri = "QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv3".to_string();
-    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
-        token_id: token_id3.clone(),
-        owner: demeter.clone(),
-        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv3".to_string()),
-        extension: extension.clone(),
-    });
-    contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+
+    // paginate the token_ids
+    let tokens = contract.all_tokens(deps.as_ref(), None, Some(1)).unwrap();
+    assert_eq!(1, tokens.tokens.len());
+    assert_eq!(vec![token_id1.clone()], tokens.tokens);
+    let tokens = contract
+        .all_tokens(deps.as_ref(), Some(token_id1.clone()), Some(3))
         .unwrap();
+    assert_eq!(1, tokens.tokens.len());
+    assert_eq!(vec![token_id2.clone()], tokens.tokens);
 
-    let result = contract
-        .image_info(
-            deps.as_ref(),
-            "QmRdTSx4v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string(),
-        )
+    // demeter gives random full (operator) power over her tokens
+    let approve_all_msg = ExecuteMsg::ApproveAll {
+        operator: String::from("random"),
+        expires: None,
+    };
+    let owner = mock_info("demeter", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
         .unwrap();
     assert_eq!(
-        result.extension.token_uri,
-        "QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2"
-    );
-    assert_eq!(
-        result.token_uri.unwrap(),
-        "QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2"
+        res,
+        Response::new()
+            .add_attribute("action", "approve_all")
+            .add_attribute("sender", "demeter")
+            .add_attribute("operator", "random")
     );
-}
 
-#[test]
-fn buying() {
-    let mut deps = mock_dependencies(&[]);
-    let contract = setup_contract(deps.as_mut());
+    // random can now transfer
+    let random = mock_info("random", &[]);
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("person"),
+        token_id: token_id1,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), random.clone(), transfer_msg)
+        .unwrap();
 
-    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
-    let attributes: Vec<Trait> = vec![
-        Trait {
-            display_type: None,
-            trait_type: "gender".to_string(),
-            value: "male".to_string(),
-        },
-        Trait {
-            display_type: None,
-            trait_type: "name".to_string(),
-            value: "Jim Morrisson".to_string(),
-        },
-    ];
-    let extension = Metadata {
-        token_uri: token_uri.clone(),
-        image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
-        image_data: None,
-        external_url: None,
-        description: None,
-        name: None,
-        attributes: Some(attributes),
-        background_color: None,
-        animation_url: None,
-        youtube_url: None,
-        current_status: None,
+    // random can now send
+    let inner_msg = WasmMsg::Execute {
+        contract_addr: "another_contract".into(),
+        msg: to_binary("You now also have the growing power").unwrap(),
+        funds: vec![],
     };
-    let buy_msg = BuyExtension {
-        male_name: "James Dean".to_string(),
-        female_name: "Norma Rae".to_string(),
+    let msg: CosmosMsg = CosmosMsg::Wasm(inner_msg);
+
+    let send_msg = ExecuteMsg::SendNft {
+        contract: String::from("another_contract"),
+        token_id: token_id2,
+        msg: to_binary(&msg).unwrap(),
+        reply_on: None,
     };
-    let json = serde_json_wasm::to_string(&extension);
-    assert!(json.is_ok(), "JSON unpacking failed");
+    contract
+        .execute(deps.as_mut(), mock_env(), random, send_msg)
+        .unwrap();
 
-    let json_string = json.unwrap();
-    //   println!("{}", &json_string);
-    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        signature: "TODO".to_string(),
-        attributes: json_string.clone(),
-        buy_metadata: buy_msg.clone(),
-    });
-
-    // no money
-    let random = mock_info("random", &[]);
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
-        .unwrap_err();
-    match err {
-        ContractError::Funds { .. } => {}
-        _ => {
-            assert!(false, "Unexpected Error {:?}", err)
-        }
-    }
+    // Approve_all, revoke_all, and check for empty, to test revoke_all
+    let approve_all_msg = ExecuteMsg::ApproveAll {
+        operator: String::from("operator"),
+        expires: None,
+    };
+    // person is now the owner of the tokens
+    let owner = mock_info("person", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
+        .unwrap();
 
-    // not enough money
-    let random = mock_info("random", &[Coin::new(2_999_999u128, "uluna")]);
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
-        .unwrap_err();
-    match err {
-        ContractError::Funds { .. } => {}
-        _ => {
-            assert!(false, "Unexpected Error {:?}", err)
+    let res = contract
+        .all_approvals(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovedForAllResponse {
+            operators: vec![cw721::Approval {
+                spender: String::from("operator"),
+                expires: Expiration::Never {}
+            }]
         }
-    }
-    //wrong type of money
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uuusd")]);
+    );
 
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
-        .unwrap_err();
-    match err {
-        ContractError::Funds { .. } => {}
-        _ => {
-            assert!(false, "Unexpected Error {:?}", err)
-        }
-    }
-    //bad signature
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    // second approval
+    let buddy_expires = Expiration::AtHeight(1234567);
+    let approve_all_msg = ExecuteMsg::ApproveAll {
+        operator: String::from("buddy"),
+        expires: Some(buddy_expires),
+    };
+    let owner = mock_info("person", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_all_msg)
+        .unwrap();
 
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
-        .unwrap_err();
-    match err {
-        ContractError::CryptoVerify { .. } => {}
-        _ => {
-            assert!(false, "Unexpected Error {:?}", err)
+    // and paginate queries
+    let res = contract
+        .all_approvals(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            true,
+            None,
+            Some(1),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovedForAllResponse {
+            operators: vec![cw721::Approval {
+                spender: String::from("buddy"),
+                expires: buddy_expires,
+            }]
         }
-    }
-    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        signature: "TODO".to_string(),
-        attributes: json_string.clone(),
-        buy_metadata: buy_msg.clone(),
-    });
-
-    //bad signature
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
-
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
-        .unwrap_err();
-    match err {
-        ContractError::CryptoVerify { .. } => {}
-        _ => {
-            assert!(false, "Unexpected Error {:?}", err)
+    );
+    let res = contract
+        .all_approvals(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            true,
+            Some(String::from("buddy")),
+            Some(2),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovedForAllResponse {
+            operators: vec![cw721::Approval {
+                spender: String::from("operator"),
+                expires: Expiration::Never {}
+            }]
         }
-    }
-    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        // signature is supposed to be generated from account sending the message + extension
-        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
-        attributes: json_string.clone(),
-        buy_metadata: buy_msg.clone(),
-    });
-    //println!("EXEC:{}", serde_json_wasm::to_string(&mint_msg).unwrap());
-    //good signature, the token_id not so much.
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    );
 
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+    let revoke_all_msg = ExecuteMsg::RevokeAll {
+        operator: String::from("operator"),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, revoke_all_msg)
+        .unwrap();
 
-    match contract_exec {
-        Ok(resp) => {
-            println!("{:?}", resp)
-        }
-        Err(err) => {
-            assert!(false, "Unexpected Error {:?}", err)
+    // Approvals are removed / cleared without affecting others
+    let res = contract
+        .all_approvals(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovedForAllResponse {
+            operators: vec![cw721::Approval {
+                spender: String::from("buddy"),
+                expires: buddy_expires,
+            }]
         }
-    }
-    //assert_eq!(err, ContractError::Unauthorized {});
+    );
 
-    // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
-    assert_eq!(1, tokens.tokens.len());
-    assert_eq!(vec!["James Dean"], tokens.tokens);
+    // ensure the filter works (nothing should be here
+    let mut late_env = mock_env();
+    late_env.block.height = 1234568; //expired
+    let res = contract
+        .all_approvals(
+            deps.as_ref(),
+            late_env,
+            String::from("person"),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(0, res.operators.len());
 }
 
 #[test]
-fn max_issued() {
+fn query_tokens_by_owner() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
-    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
-    let attributes: Vec<Trait> = vec![
-        Trait {
-            display_type: None,
-            trait_type: "gender".to_string(),
-            value: "male".to_string(),
-        },
-        Trait {
-            display_type: None,
-            trait_type: "name".to_string(),
-            value: "Jim Morrisson".to_string(),
-        },
-    ];
-    let extension = Metadata {
-        token_uri: token_uri.clone(),
+    let minter = mock_info(MINTER, &[]);
+
+    // Mint a couple tokens (from the same owner)
+    let token_id1 = "grow1".to_string();
+    let demeter = String::from("Demeter");
+    let token_id2 = "grow2".to_string();
+    let ceres = String::from("Ceres");
+    let token_id3 = "sing".to_string();
+    let mut extension = Metadata {
+        token_uri: token_id1.clone(),
         image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
         image_data: None,
         external_url: None,
         description: None,
         name: None,
-        attributes: Some(attributes),
+        attributes: None,
         background_color: None,
         animation_url: None,
         youtube_url: None,
         current_status: None,
     };
-    let buy_msg = BuyExtension {
-        male_name: "James Dean".to_string(),
-        female_name: "Norma Rae".to_string(),
-    };
-    let json = serde_json_wasm::to_string(&extension);
-    assert!(json.is_ok(), "JSON unpacking failed");
-
-    let json_string = json.unwrap();
-
-    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
-        attributes: json_string.clone(),
-        buy_metadata: buy_msg.clone(),
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id1.clone(),
+        owner: demeter.clone(),
+        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv1".to_string()),
+        extension: extension.clone(),
     });
-    //good signature
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
 
-    match contract_exec {
-        Err(err) => {
-            assert!(false, "Unexpected Error {:?}", err)
-        }
-        _ => {}
-    }
-    //    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
-
-    let buy_msg = BuyExtension {
-        male_name: "Jimmy Sparks".to_string(),
-        female_name: "Florence O'Niel".to_string(),
-    };
-    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
-        attributes: json_string.clone(),
-        buy_metadata: buy_msg.clone(),
+    extension.image = Some("QmRdTSx4v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id2.clone(),
+        owner: ceres.clone(),
+        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2".to_string()),
+        extension: extension.clone(),
     });
-    //good signature, but should have been claimed
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+    extension.image = Some("QmRdTSx5v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
 
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
-        .unwrap_err();
-    match err {
-        ContractError::Claimed {} => {}
-        _ => {
-            assert!(
-                false,
-                "Unexpected Error. should have been claimed. Token URI duplicate {:?}",
-                err
-            )
-        }
-    }
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id3.clone(),
+        owner: demeter.clone(),
+        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv3".to_string()),
+        extension: extension.clone(),
+    });
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
 
+    // get all tokens in order:
+    let expected = vec![token_id1.clone(), token_id2.clone(), token_id3.clone()];
     let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    assert_eq!(&expected, &tokens.tokens);
+    // paginate
+    let tokens = contract.all_tokens(deps.as_ref(), None, Some(2)).unwrap();
+    assert_eq!(&expected[..2], &tokens.tokens[..]);
+    let tokens = contract
+        .all_tokens(deps.as_ref(), Some(expected[1].clone()), None)
+        .unwrap();
+    assert_eq!(&expected[2..], &tokens.tokens[..]);
 
-    assert_eq!(tokens.tokens.len(), 1);
-    let buy_msg = BuyExtension {
-        male_name: "Peter Walker".to_string(),
-        female_name: "Lady Ga Ga".to_string(),
-    };
-
-    let json_string = r#"{"token_uri":"https://www.merriam-webster.com/dictionary/token2","image":"QASD","image_data":null,"external_url":null,"description":null,"name":null,"attributes":[{"display_type":null,"trait_type":"gender","value":"male"},{"display_type":null,"trait_type":"name","value":"James T. Kirk"}],"background_color":null,"animation_url":null,"youtube_url":null,"current_status":null}"#;
-    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        signature: "Ahl64fqr8orUI+Mli/h179wRnF8YRkde/GgUa2dR5vJ1gsT1Ay72wP1Q9DrS/xhjZazDNsUkuHCU1oM2s+LFhA==".to_string(),
-        attributes:String::from( json_string),
-        buy_metadata: buy_msg.clone(),
-    });
+    // get by owner
+    let by_ceres = vec![token_id2];
+    let by_demeter = vec![token_id1, token_id3];
+    // all tokens by owner
+    let tokens = contract
+        .tokens(deps.as_ref(), demeter.clone(), None, None)
+        .unwrap();
+    assert_eq!(&by_demeter, &tokens.tokens);
+    let tokens = contract.tokens(deps.as_ref(), ceres, None, None).unwrap();
+    assert_eq!(&by_ceres, &tokens.tokens);
 
-    //good signature,  token #2
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    // paginate for demeter
+    let tokens = contract
+        .tokens(deps.as_ref(), demeter.clone(), None, Some(1))
+        .unwrap();
+    assert_eq!(&by_demeter[..1], &tokens.tokens[..]);
+    let tokens = contract
+        .tokens(deps.as_ref(), demeter, Some(by_demeter[0].clone()), Some(3))
+        .unwrap();
+    assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
+}
 
-    let contract_response = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+#[test]
+fn query_tokens_by_image() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
 
-    match contract_response {
-        Ok(_resp) => {}
-        _ => {
-            assert!(false, "Unexpected Error. token#2 {:?}", err)
-        }
-    }
-    let buy_msg = BuyExtension {
-        male_name: "Evan Green".to_string(),
-        female_name: "Agatha Tokra".to_string(),
+    // Mint a couple tokens (from the same owner)
+    let token_id1 = "grow1".to_string();
+    let demeter = String::from("Demeter");
+    let token_id2 = "grow2".to_string();
+    let ceres = String::from("Ceres");
+    let token_id3 = "sing".to_string();
+    let mut extension = Metadata {
+        token_uri: token_id1.clone(),
+        image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
+        image_data: None,
+        external_url: None,
+        description: None,
+        name: None,
+        attributes: None,
+        background_color: None,
+        animation_url: None,
+        youtube_url: None,
+        current_status: None,
     };
-
-    //let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
-
-    let json_string = r#"{"token_uri":"https://www.merriam-webster.com/dictionary/token3","image":"XYZ","image_data":null,"external_url":null,"description":null,"name":null,"attributes":[{"display_type":null,"trait_type":"gender","value":"female"},{"display_type":null,"trait_type":"name","value":"James T. Kirk"}],"background_color":null,"animation_url":null,"youtube_url":null,"current_status":null}"#;
-
-    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        // signature is supposed to be generated from account sending the message + extension
-        signature: "QaCk+WUonQMwN/UtuKsHKogEhA5pUheCD4z0hZJ16n4HmAjCd/kuSSmsCGOsaCWfyCN6nVQPSRLr2dGDePClrA==".to_string(),
-        attributes: String::from(json_string),
-        buy_metadata: buy_msg.clone(),
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id1.clone(),
+        owner: demeter.clone(),
+        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv1".to_string()),
+        extension: extension.clone(),
     });
-    //good signature, the token_id not so much.
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
-
-    match contract_exec {
-        Ok(_resp) => {}
-        Err(err) => {
-            assert!(false, "Unexpected Error {:?}", err)
-        }
-    }
-    let buy_msg = BuyExtension {
-        male_name: "Adam Smith".to_string(),
-        female_name: "Natalie Parker".to_string(),
-    };
-
-    //    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
-
-    let json_string = r#"{"token_uri":"https://www.merriam-webster.com/dictionary/token4","image":"A123","image_data":null,"external_url":null,"description":null,"name":null,"attributes":[{"display_type":null,"trait_type":"gender","value":"female"},{"display_type":null,"trait_type":"name","value":"James T. Kirk"}],"background_color":null,"animation_url":null,"youtube_url":null}"#;
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
 
-    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        signature: "3/18ROI5T4oFpy+2bIps5QFBVWwBPIqq+ZNgBmGbqnMFuyeGnftjk5QCX+akUPSHmYnr2AX2nnBOr4WDbkOduA==".to_string(),
-        attributes: String::from(json_string),
-        buy_metadata: buy_msg.clone(),
+    extension.image = Some("QmRdTSx4v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
+    extension.token_uri = "QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2".to_string();
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id2.clone(),
+        owner: ceres.clone(),
+        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2".to_string()),
+        extension: extension.clone(),
     });
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+    extension.image = Some("QmRdTSx5v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string());
+    extension.token_uri = "QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv3".to_string();
+    let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: token_id3.clone(),
+        owner: demeter.clone(),
+        token_uri: Some("QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv3".to_string()),
+        extension: extension.clone(),
+    });
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
 
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
-        .unwrap_err();
-    match err {
-        ContractError::MaxIssued {} => {}
-        _ => {
-            assert!(
-                false,
-                "Unexpected Error. We've claimed more than we have {:?}",
-                err
-            )
-        }
-    }
-    // list the token_ids
-    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
-    println!("{}", tokens.tokens.join(","));
-    assert_eq!(3, tokens.tokens.len());
-
+    let result = contract
+        .image_info(
+            deps.as_ref(),
+            "QmRdTSx4v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string(),
+        )
+        .unwrap();
     assert_eq!(
-        true,
-        tokens
-            .tokens
-            .iter()
-            .find(|x| x.clone() == &String::from("James Dean"))
-            .is_some()
+        result.extension.token_uri,
+        "QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2"
+    );
+    assert_eq!(
+        result.token_uri.unwrap(),
+        "QmcTLCzFDJDf4hSKHn75MTVifwWNobiB4ZvXtYMTxUVcv2"
     );
 }
 
 #[test]
-fn set_mint_amount() {
+fn buying() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
+
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
     let attributes: Vec<Trait> = vec![
         Trait {
@@ -1287,71 +1807,156 @@ fn set_mint_amount() {
     assert!(json.is_ok(), "JSON unpacking failed");
 
     let json_string = json.unwrap();
-
+    //   println!("{}", &json_string);
     let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
+        signature: "TODO".to_string(),
         attributes: json_string.clone(),
         buy_metadata: buy_msg.clone(),
     });
-    //good signature
-    let random = mock_info("random", &[Coin::new(2_000_000u128, "uluna")]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
 
-    match contract_exec {
-        Err(ContractError::Funds { .. }) => {}
-        Err(err) => {
+    // no money
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    match err {
+        ContractError::Funds { .. } => {}
+        _ => {
             assert!(false, "Unexpected Error {:?}", err)
         }
+    }
+
+    // not enough money
+    let random = mock_info("random", &[Coin::new(2_999_999u128, "uluna")]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    match err {
+        ContractError::Funds { .. } => {}
         _ => {
-            assert!(false, "Contract should not have worked")
+            assert!(false, "Unexpected Error {:?}", err)
         }
     }
-    let mint_price_msg = ExecuteMsg::<Extension>::SetMintAmount {
-        mint_amount: 2_000_000,
-    };
-    let random = mock_info("random", &[]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_price_msg.clone());
-    match contract_exec {
-        Err(ContractError::Unauthorized { .. }) => {}
-        Err(err) => {
-            assert!(false, "Unexpected Error {:?}", err)
-        }
+    //wrong type of money
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uuusd")]);
+
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    match err {
+        ContractError::Funds { .. } => {}
         _ => {
-            assert!(false, "Contract should not have worked")
+            assert!(false, "Unexpected Error {:?}", err)
         }
     }
+    //bad signature
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
 
-    let random = mock_info(MINTER, &[]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_price_msg.clone());
-    match contract_exec {
-        Err(err) => {
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    match err {
+        ContractError::CryptoVerify { .. } => {}
+        _ => {
             assert!(false, "Unexpected Error {:?}", err)
         }
-        _ => {}
     }
-    let random = mock_info("random", &[Coin::new(1_999_999u128, "uluna")]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
-    match contract_exec {
-        Err(ContractError::Funds { .. }) => {}
-        Err(err) => {
+    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        signature: "TODO".to_string(),
+        attributes: json_string.clone(),
+        buy_metadata: buy_msg.clone(),
+    });
+
+    //bad signature
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    match err {
+        ContractError::CryptoVerify { .. } => {}
+        _ => {
             assert!(false, "Unexpected Error {:?}", err)
         }
-        _ => {
-            assert!(false, "Contract should not have worked")
+    }
+    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        // signature is supposed to be generated from account sending the message + extension
+        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
+        attributes: json_string.clone(),
+        buy_metadata: buy_msg.clone(),
+    });
+    // the names aren't covered by the signature, they are held to the content policy
+    for (male_name, expected) in [
+        ("James\u{200b}Dean", "invalid character"),
+        (" James Dean", "whitespace"),
+    ] {
+        let name_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+            signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
+            attributes: json_string.clone(),
+            buy_metadata: BuyExtension {
+                male_name: male_name.to_string(),
+                female_name: "Norma Rae".to_string(),
+            },
+        });
+        let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+        let err = contract
+            .execute(deps.as_mut(), mock_env(), random, name_msg)
+            .unwrap_err();
+        match (err, expected) {
+            (ContractError::InvalidCharacter { .. }, "invalid character") => {}
+            (ContractError::NameWhitespace {}, "whitespace") => {}
+            (err, _) => panic!("Unexpected Error {:?}", err),
         }
     }
-    let random = mock_info("random", &[Coin::new(2_000_000u128, "uluna")]);
+    // and to the blocklist
+    let blocked = vec!["james dean".to_string()];
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::AddBlockedNames {
+                names: blocked.clone(),
+            },
+        )
+        .unwrap();
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    assert!(matches!(err, ContractError::NameBlocked {}));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::RemoveBlockedNames { names: blocked },
+        )
+        .unwrap();
+    //println!("EXEC:{}", serde_json_wasm::to_string(&mint_msg).unwrap());
+    //good signature, the token_id not so much.
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+
     let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+
     match contract_exec {
+        Ok(resp) => {
+            println!("{:?}", resp)
+        }
         Err(err) => {
             assert!(false, "Unexpected Error {:?}", err)
         }
-        _ => {}
     }
+    //assert_eq!(err, ContractError::Unauthorized {});
+
+    // list the token_ids
+    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    assert_eq!(1, tokens.tokens.len());
+    assert_eq!(vec!["James Dean"], tokens.tokens);
 }
 
 #[test]
-fn set_public_key() {
+fn max_issued() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
@@ -1367,7 +1972,7 @@ fn set_public_key() {
             value: "Jim Morrisson".to_string(),
         },
     ];
-    let mut extension = Metadata {
+    let extension = Metadata {
         token_uri: token_uri.clone(),
         image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
         image_data: None,
@@ -1404,73 +2009,131 @@ fn set_public_key() {
         }
         _ => {}
     }
-    // Phrase words - fiction artefact enjoy bicycle agent jungle another mesh item slam voice motion reflect code jewel tunnel glory hobby access that asthma ethics volcano cargo
-    // Public Key: AqNQdMoVoy8Ub5/sh2q6UYk1Di1BTpm7hoL83wQe0nZL
-    // signature: gMyokP8J9N51ouzcq8nZ6SAR6zWYrXWqo1jtzFcrL718sxvFDKyOp2uqqNxSeitbiEU7jpj7To1rdDxVZPV2IA==
-    let set_pubkey_msg = ExecuteMsg::<Extension>::SetPublicKey {
-        public_key: "AqNQdMoVoy8Ub5/sh2q6UYk1Di1BTpm7hoL83wQe0nZL".to_string(),
+    //    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+
+    let buy_msg = BuyExtension {
+        male_name: "Jimmy Sparks".to_string(),
+        female_name: "Florence O'Niel".to_string(),
     };
-    let random = mock_info("random", &[]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, set_pubkey_msg.clone());
-    match contract_exec {
-        Err(ContractError::Unauthorized { .. }) => {}
-        Err(err) => {
-            assert!(false, "Unexpected Error {:?}", err)
-        }
+    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
+        attributes: json_string.clone(),
+        buy_metadata: buy_msg.clone(),
+    });
+    //good signature, but should have been claimed
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    match err {
+        ContractError::Claimed {} => {}
         _ => {
-            assert!(false, "Contract should not have worked")
+            assert!(
+                false,
+                "Unexpected Error. should have been claimed. Token URI duplicate {:?}",
+                err
+            )
         }
     }
 
-    let random = mock_info(MINTER, &[]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, set_pubkey_msg.clone());
-    match contract_exec {
-        Err(err) => {
-            assert!(false, "Unexpected Error {:?}", err)
+    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+
+    assert_eq!(tokens.tokens.len(), 1);
+    let buy_msg = BuyExtension {
+        male_name: "Peter Walker".to_string(),
+        female_name: "Lady Ga Ga".to_string(),
+    };
+
+    let json_string = r#"{"token_uri":"https://www.merriam-webster.com/dictionary/token2","image":"QASD","image_data":null,"external_url":null,"description":null,"name":null,"attributes":[{"display_type":null,"trait_type":"gender","value":"male"},{"display_type":null,"trait_type":"name","value":"James T. Kirk"}],"background_color":null,"animation_url":null,"youtube_url":null,"current_status":null}"#;
+    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        signature: "Ahl64fqr8orUI+Mli/h179wRnF8YRkde/GgUa2dR5vJ1gsT1Ay72wP1Q9DrS/xhjZazDNsUkuHCU1oM2s+LFhA==".to_string(),
+        attributes:String::from( json_string),
+        buy_metadata: buy_msg.clone(),
+    });
+
+    //good signature,  token #2
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+
+    let contract_response = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+
+    match contract_response {
+        Ok(_resp) => {}
+        _ => {
+            assert!(false, "Unexpected Error. token#2 {:?}", err)
         }
-        _ => {}
     }
+    let buy_msg = BuyExtension {
+        male_name: "Evan Green".to_string(),
+        female_name: "Agatha Tokra".to_string(),
+    };
+
+    //let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+
+    let json_string = r#"{"token_uri":"https://www.merriam-webster.com/dictionary/token3","image":"XYZ","image_data":null,"external_url":null,"description":null,"name":null,"attributes":[{"display_type":null,"trait_type":"gender","value":"female"},{"display_type":null,"trait_type":"name","value":"James T. Kirk"}],"background_color":null,"animation_url":null,"youtube_url":null,"current_status":null}"#;
 
+    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        // signature is supposed to be generated from account sending the message + extension
+        signature: "QaCk+WUonQMwN/UtuKsHKogEhA5pUheCD4z0hZJ16n4HmAjCd/kuSSmsCGOsaCWfyCN6nVQPSRLr2dGDePClrA==".to_string(),
+        attributes: String::from(json_string),
+        buy_metadata: buy_msg.clone(),
+    });
+    //good signature, the token_id not so much.
     let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
     let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+
     match contract_exec {
-        Err(ContractError::BadSignature {}) => {}
+        Ok(_resp) => {}
         Err(err) => {
             assert!(false, "Unexpected Error {:?}", err)
         }
-        _ => {
-            assert!(false, "Should have failed")
-        }
     }
-    extension.token_uri = "https://example.com".to_string();
-    extension.image = Some("QWESDSD".to_string());
+    let buy_msg = BuyExtension {
+        male_name: "Adam Smith".to_string(),
+        female_name: "Natalie Parker".to_string(),
+    };
 
-    let json = serde_json_wasm::to_string(&extension);
-    assert!(json.is_ok(), "JSON unpacking failed");
+    //    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
 
-    let json_string = json.unwrap();
-    println!("{}", json_string);
+    let json_string = r#"{"token_uri":"https://www.merriam-webster.com/dictionary/token4","image":"A123","image_data":null,"external_url":null,"description":null,"name":null,"attributes":[{"display_type":null,"trait_type":"gender","value":"female"},{"display_type":null,"trait_type":"name","value":"James T. Kirk"}],"background_color":null,"animation_url":null,"youtube_url":null}"#;
 
-    // this fails as we actually 'bought' the NFT in the first message. but it gets past the signature check so it works
     let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
-        signature: "gJrUgYcsF4M0VkWxTc1r/HtYK3q7FYKxDcGrCyAlY6U8bogSqAidoV2RRWfIkNsqKTIdP6+SbBxFXoafBf2PSg==".to_string(),
-        attributes: json_string.clone(),
+        signature: "3/18ROI5T4oFpy+2bIps5QFBVWwBPIqq+ZNgBmGbqnMFuyeGnftjk5QCX+akUPSHmYnr2AX2nnBOr4WDbkOduA==".to_string(),
+        attributes: String::from(json_string),
         buy_metadata: buy_msg.clone(),
     });
 
     let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
-    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
-    match contract_exec {
-        Err(ContractError::Claimed {}) => {}
-        Err(err) => {
-            assert!(false, "Unexpected Error {:?}", err)
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    match err {
+        ContractError::MaxIssued {} => {}
+        _ => {
+            assert!(
+                false,
+                "Unexpected Error. We've claimed more than we have {:?}",
+                err
+            )
         }
-        _ => {}
     }
+    // list the token_ids
+    let tokens = contract.all_tokens(deps.as_ref(), None, None).unwrap();
+    println!("{}", tokens.tokens.join(","));
+    assert_eq!(3, tokens.tokens.len());
+
+    assert_eq!(
+        true,
+        tokens
+            .tokens
+            .iter()
+            .find(|x| x.clone() == &String::from("James Dean"))
+            .is_some()
+    );
 }
 
 #[test]
-fn set_status() {
+fn set_mint_amount() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
@@ -1514,35 +2177,255 @@ fn set_status() {
         buy_metadata: buy_msg.clone(),
     });
     //good signature
-    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    let random = mock_info("random", &[Coin::new(2_000_000u128, "uluna")]);
     let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
 
     match contract_exec {
+        Err(ContractError::Funds { .. }) => {}
         Err(err) => {
             assert!(false, "Unexpected Error {:?}", err)
         }
-        Ok(t) => {
-            let token_id_opt = t
-                .attributes
-                .iter()
-                .find(|t| t.key == "token_id")
-                .map(|f| f.value.clone());
-            if let Some(token_id) = token_id_opt {
-                assert_eq!(token_id, "James Dean");
-
-                let res = contract.nft_info(deps.as_ref(), token_id.clone()).unwrap();
-                let status = res.extension.get_status();
-                assert!(status.is_some(), "Should have seen a status");
-                assert_eq!(status.unwrap(), "Alive and curious");
-                let set_status_msg = ExecuteMsg::<Extension>::SetTokenStatus {
-                    status: "Peeping the life fantastic".to_string(),
-                    token_id: token_id.clone(),
-                };
-                let random = mock_info("random", &[]);
-                let contract_exec =
-                    contract.execute(deps.as_mut(), mock_env(), random, set_status_msg.clone());
-                match contract_exec {
-                    Err(err) => {
+        _ => {
+            assert!(false, "Contract should not have worked")
+        }
+    }
+    let mint_price_msg = ExecuteMsg::<Extension>::SetMintAmount {
+        mint_amount: 2_000_000,
+    };
+    let random = mock_info("random", &[]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_price_msg.clone());
+    match contract_exec {
+        Err(ContractError::Unauthorized { .. }) => {}
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {
+            assert!(false, "Contract should not have worked")
+        }
+    }
+
+    let random = mock_info(MINTER, &[]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_price_msg.clone());
+    match contract_exec {
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {}
+    }
+    let random = mock_info("random", &[Coin::new(1_999_999u128, "uluna")]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+    match contract_exec {
+        Err(ContractError::Funds { .. }) => {}
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {
+            assert!(false, "Contract should not have worked")
+        }
+    }
+    let random = mock_info("random", &[Coin::new(2_000_000u128, "uluna")]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+    match contract_exec {
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn set_public_key() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+    let attributes: Vec<Trait> = vec![
+        Trait {
+            display_type: None,
+            trait_type: "gender".to_string(),
+            value: "male".to_string(),
+        },
+        Trait {
+            display_type: None,
+            trait_type: "name".to_string(),
+            value: "Jim Morrisson".to_string(),
+        },
+    ];
+    let mut extension = Metadata {
+        token_uri: token_uri.clone(),
+        image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
+        image_data: None,
+        external_url: None,
+        description: None,
+        name: None,
+        attributes: Some(attributes),
+        background_color: None,
+        animation_url: None,
+        youtube_url: None,
+        current_status: None,
+    };
+    let buy_msg = BuyExtension {
+        male_name: "James Dean".to_string(),
+        female_name: "Norma Rae".to_string(),
+    };
+    let json = serde_json_wasm::to_string(&extension);
+    assert!(json.is_ok(), "JSON unpacking failed");
+
+    let json_string = json.unwrap();
+
+    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
+        attributes: json_string.clone(),
+        buy_metadata: buy_msg.clone(),
+    });
+    //good signature
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+
+    match contract_exec {
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {}
+    }
+    // Phrase words - fiction artefact enjoy bicycle agent jungle another mesh item slam voice motion reflect code jewel tunnel glory hobby access that asthma ethics volcano cargo
+    // Public Key: AqNQdMoVoy8Ub5/sh2q6UYk1Di1BTpm7hoL83wQe0nZL
+    // signature: gMyokP8J9N51ouzcq8nZ6SAR6zWYrXWqo1jtzFcrL718sxvFDKyOp2uqqNxSeitbiEU7jpj7To1rdDxVZPV2IA==
+    let set_pubkey_msg = ExecuteMsg::<Extension>::SetPublicKey {
+        public_key: "AqNQdMoVoy8Ub5/sh2q6UYk1Di1BTpm7hoL83wQe0nZL".to_string(),
+    };
+    let random = mock_info("random", &[]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, set_pubkey_msg.clone());
+    match contract_exec {
+        Err(ContractError::Unauthorized { .. }) => {}
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {
+            assert!(false, "Contract should not have worked")
+        }
+    }
+
+    let random = mock_info(MINTER, &[]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, set_pubkey_msg.clone());
+    match contract_exec {
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {}
+    }
+
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+    match contract_exec {
+        Err(ContractError::BadSignature {}) => {}
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {
+            assert!(false, "Should have failed")
+        }
+    }
+    extension.token_uri = "https://example.com".to_string();
+    extension.image = Some("QWESDSD".to_string());
+
+    let json = serde_json_wasm::to_string(&extension);
+    assert!(json.is_ok(), "JSON unpacking failed");
+
+    let json_string = json.unwrap();
+    println!("{}", json_string);
+
+    // this fails as we actually 'bought' the NFT in the first message. but it gets past the signature check so it works
+    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        signature: "gJrUgYcsF4M0VkWxTc1r/HtYK3q7FYKxDcGrCyAlY6U8bogSqAidoV2RRWfIkNsqKTIdP6+SbBxFXoafBf2PSg==".to_string(),
+        attributes: json_string.clone(),
+        buy_metadata: buy_msg.clone(),
+    });
+
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+    match contract_exec {
+        Err(ContractError::Claimed {}) => {}
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn set_status() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+    let attributes: Vec<Trait> = vec![
+        Trait {
+            display_type: None,
+            trait_type: "gender".to_string(),
+            value: "male".to_string(),
+        },
+        Trait {
+            display_type: None,
+            trait_type: "name".to_string(),
+            value: "Jim Morrisson".to_string(),
+        },
+    ];
+    let extension = Metadata {
+        token_uri: token_uri.clone(),
+        image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
+        image_data: None,
+        external_url: None,
+        description: None,
+        name: None,
+        attributes: Some(attributes),
+        background_color: None,
+        animation_url: None,
+        youtube_url: None,
+        current_status: None,
+    };
+    let buy_msg = BuyExtension {
+        male_name: "James Dean".to_string(),
+        female_name: "Norma Rae".to_string(),
+    };
+    let json = serde_json_wasm::to_string(&extension);
+    assert!(json.is_ok(), "JSON unpacking failed");
+
+    let json_string = json.unwrap();
+
+    let mint_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
+        attributes: json_string.clone(),
+        buy_metadata: buy_msg.clone(),
+    });
+    //good signature
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, mint_msg.clone());
+
+    match contract_exec {
+        Err(err) => {
+            assert!(false, "Unexpected Error {:?}", err)
+        }
+        Ok(t) => {
+            let token_id_opt = t
+                .attributes
+                .iter()
+                .find(|t| t.key == "token_id")
+                .map(|f| f.value.clone());
+            if let Some(token_id) = token_id_opt {
+                assert_eq!(token_id, "James Dean");
+
+                let res = contract.nft_info(deps.as_ref(), token_id.clone()).unwrap();
+                let status = res.extension.get_status();
+                assert!(status.is_some(), "Should have seen a status");
+                assert_eq!(status.unwrap(), "Alive and curious");
+                let set_status_msg = ExecuteMsg::<Extension>::SetTokenStatus {
+                    status: "Peeping the life fantastic".to_string(),
+                    token_id: token_id.clone(),
+                };
+                let random = mock_info("random", &[]);
+                let contract_exec =
+                    contract.execute(deps.as_mut(), mock_env(), random, set_status_msg.clone());
+                match contract_exec {
+                    Err(err) => {
                         assert!(false, "Unexpected Error {:?}", err)
                     }
                     _ => {}
@@ -1706,10 +2589,12 @@ fn set_nft_trait_map() {
                     NftTraitSummary {
                         label: "A".to_string(),
                         value: Decimal::from_str("0.90").unwrap(),
+                        weight: None,
                     },
                     NftTraitSummary {
                         label: "B".to_string(),
                         value: Decimal::from_str("0.10").unwrap(),
+                        weight: None,
                     },
                 ],
             ),
@@ -1719,14 +2604,17 @@ fn set_nft_trait_map() {
                     NftTraitSummary {
                         label: "m".to_string(),
                         value: Decimal::from_str("0.40").unwrap(),
+                        weight: None,
                     },
                     NftTraitSummary {
                         label: "n".to_string(),
                         value: Decimal::from_str("0.10").unwrap(),
+                        weight: None,
                     },
                     NftTraitSummary {
                         label: "0".to_string(),
                         value: Decimal::from_str("0.10").unwrap(),
+                        weight: None,
                     },
                 ],
             ),
@@ -1769,6 +2657,8 @@ fn do_sweep() {
 
     let sweep_msg = ExecuteMsg::Sweep {
         denom: "uluna".to_string(),
+        amount: None,
+        recipient: None,
     };
     let random = mock_info("random", &[]);
     let contract_exec = contract.execute(deps.as_mut(), mock_env(), random, sweep_msg.clone());
@@ -1846,6 +2736,8 @@ fn do_sweep() {
     }
     let sweep_msg = ExecuteMsg::Sweep {
         denom: "uusd".to_string(),
+        amount: None,
+        recipient: None,
     };
     let minter = mock_info(MINTER, &[]);
     let contract_exec = contract.execute(deps.as_mut(), mock_env(), minter, sweep_msg.clone());
@@ -1861,9 +2753,10 @@ fn do_sweep() {
 }
 
 #[test]
-fn change_name() {
+fn refunds_and_nonpayable() {
     let mut deps = mock_dependencies(&[]);
     let contract = setup_contract(deps.as_mut());
+
     let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
     let attributes: Vec<Trait> = vec![
         Trait {
@@ -1878,21 +2771,420 @@ fn change_name() {
         },
     ];
     let extension = Metadata {
-        token_uri: token_uri.clone(),
+        token_uri,
         image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
-        image_data: None,
-        external_url: None,
-        description: None,
-        name: None,
         attributes: Some(attributes),
-        background_color: None,
-        animation_url: None,
-        youtube_url: None,
-        current_status: None,
-    };
-    let buy_msg = BuyExtension {
-        male_name: "James Dean".to_string(),
-        female_name: "Norma Rae".to_string(),
+        ..Metadata::default()
+    };
+    let buy_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
+        attributes: serde_json_wasm::to_string(&extension).unwrap(),
+        buy_metadata: BuyExtension {
+            male_name: "James Dean".to_string(),
+            female_name: "Norma Rae".to_string(),
+        },
+    });
+
+    // a second denom is not welcome
+    let random = mock_info(
+        "random",
+        &[
+            Coin::new(3_000_000u128, "uluna"),
+            Coin::new(1_000_000u128, "uusd"),
+        ],
+    );
+    match contract.execute(deps.as_mut(), mock_env(), random, buy_msg.clone()) {
+        Err(ContractError::Funds {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+
+    // paying too much gets the difference back
+    let random = mock_info("random", &[Coin::new(4_000_000u128, "uluna")]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), random, buy_msg)
+        .unwrap();
+    assert_eq!(
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "random".to_string(),
+            amount: vec![Coin::new(1_000_000u128, "uluna")],
+        })],
+        res.messages
+    );
+
+    // and messages that don't take payment, don't
+    let transfer_msg = ExecuteMsg::TransferNft {
+        recipient: String::from("venus"),
+        token_id: "James Dean".to_string(),
+    };
+    let random = mock_info("random", &[Coin::new(1u128, "uluna")]);
+    match contract.execute(deps.as_mut(), mock_env(), random, transfer_msg.clone()) {
+        Err(ContractError::Payment(PaymentError::NonPayable {})) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let random = mock_info("random", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .unwrap();
+}
+
+#[test]
+fn change_pricing() {
+    let flat = ChangePricing::Flat {
+        amount: Uint128::new(5),
+    };
+    assert_eq!(Uint128::new(5), flat.price(0).unwrap());
+    assert_eq!(Uint128::new(5), flat.price(9).unwrap());
+    let linear = ChangePricing::Linear {
+        base: Uint128::new(10),
+        step: Uint128::new(3),
+    };
+    assert_eq!(Uint128::new(10), linear.price(0).unwrap());
+    assert_eq!(Uint128::new(16), linear.price(2).unwrap());
+    let exponential = ChangePricing::Exponential {
+        base: Uint128::new(1_000),
+        ratio: Decimal::from_str("1.5").unwrap(),
+    };
+    assert_eq!(Uint128::new(1_000), exponential.price(0).unwrap());
+    assert_eq!(Uint128::new(2_250), exponential.price(2).unwrap());
+    // overflow is an error, not a panic
+    assert!(exponential.price(1_000).is_err());
+    assert!(exponential.price(u64::MAX).is_err());
+    // prices stop rising at MAX_EXPONENT
+    let gentle = ChangePricing::Exponential {
+        base: Uint128::new(1_000_000),
+        ratio: Decimal::from_str("1.001").unwrap(),
+    };
+    assert_eq!(Uint128::new(1_001_000), gentle.price(1).unwrap());
+    assert_eq!(
+        gentle.price(ChangePricing::MAX_EXPONENT).unwrap(),
+        gentle.price(u64::MAX).unwrap()
+    );
+    let falling = ChangePricing::Exponential {
+        base: Uint128::new(1_000),
+        ratio: Decimal::percent(50),
+    };
+    assert_eq!(Uint128::new(250), falling.price(2).unwrap());
+    assert_eq!(Uint128::zero(), falling.price(u64::MAX).unwrap());
+    let table = ChangePricing::StepTable {
+        steps: vec![Uint128::zero(), Uint128::new(7), Uint128::new(20)],
+    };
+    assert_eq!(Uint128::zero(), table.price(0).unwrap());
+    assert_eq!(Uint128::new(7), table.price(1).unwrap());
+    assert_eq!(Uint128::new(20), table.price(50).unwrap());
+    let stepped = ChangePricing::Stepped {
+        amount: Uint128::new(100),
+        every: 2,
+    };
+    assert_eq!(Uint128::zero(), stepped.price(1).unwrap());
+    assert_eq!(Uint128::new(200), stepped.price(5).unwrap());
+    let stepped = ChangePricing::Stepped {
+        amount: Uint128::new(100),
+        every: 0,
+    };
+    assert_eq!(Uint128::zero(), stepped.price(5).unwrap());
+
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "peep", "venus");
+    let quote = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, token_id: &str, kind: ChangeKind| {
+        let res: Coin = from_binary(
+            &contract
+                .query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::QuoteChange {
+                        token_id: token_id.to_string(),
+                        kind,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        res
+    };
+    // a zero multiplier used to divide by zero
+    let minter = mock_info(MINTER, &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::SetChangeTimesMultiplier {
+                change_multiplier: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        Coin::new(0, "uusd"),
+        quote(&deps, "peep", ChangeKind::Rename)
+    );
+
+    let pricing_msg = ExecuteMsg::SetChangePricing {
+        kind: ChangeKind::Rename,
+        pricing: ChangePricing::Linear {
+            base: Uint128::new(1_000_000),
+            step: Uint128::new(500_000),
+        },
+    };
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("venus", &[]),
+        pricing_msg.clone(),
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, pricing_msg)
+        .unwrap();
+    assert_eq!(
+        Coin::new(1_000_000, "uusd"),
+        quote(&deps, "peep", ChangeKind::Rename)
+    );
+    assert_eq!(
+        Coin::new(0, "uusd"),
+        quote(&deps, "peep", ChangeKind::Description)
+    );
+
+    // descriptions are still free, renames are not
+    let describe_msg = ExecuteMsg::SetTokenNameDescription {
+        description: Some("a peep".to_string()),
+        name: None,
+        token_id: "peep".to_string(),
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            describe_msg,
+        )
+        .unwrap();
+    let rename_msg = ExecuteMsg::SetTokenNameDescription {
+        description: None,
+        name: Some("Petey".to_string()),
+        token_id: "peep".to_string(),
+    };
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("venus", &[Coin::new(1_000_000, "uusd")]),
+        rename_msg.clone(),
+    ) {
+        Err(ContractError::Funds {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    assert_eq!(
+        Coin::new(1_500_000, "uusd"),
+        quote(&deps, "peep", ChangeKind::Rename)
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_500_000, "uusd")]),
+            rename_msg,
+        )
+        .unwrap();
+    assert_eq!(
+        Coin::new(2_000_000, "uusd"),
+        quote(&deps, "Petey", ChangeKind::Rename)
+    );
+}
+
+#[test]
+fn distribute_to_payees() {
+    let mut deps = mock_dependencies(&[Coin::new(1_000_000u128, "uluna")]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+    let random = mock_info("random", &[]);
+    let distribute_msg = ExecuteMsg::Distribute {
+        denom: "uluna".to_string(),
+    };
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        random.clone(),
+        distribute_msg.clone(),
+    ) {
+        Err(ContractError::NoPayees {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+
+    let set_payees = |artist: &str, dev: &str| ExecuteMsg::SetPayees {
+        payees: vec![
+            PayeeMsg {
+                address: "artist".to_string(),
+                share: Decimal::from_str(artist).unwrap(),
+            },
+            PayeeMsg {
+                address: "dev".to_string(),
+                share: Decimal::from_str(dev).unwrap(),
+            },
+        ],
+    };
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        minter.clone(),
+        set_payees("0.7", "0.2"),
+    ) {
+        Err(ContractError::InvalidShares {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        random.clone(),
+        set_payees("0.7", "0.3"),
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            set_payees("0.7", "0.3"),
+        )
+        .unwrap();
+
+    // the minter can't sweep it all up anymore
+    let sweep_msg = ExecuteMsg::Sweep {
+        denom: "uluna".to_string(),
+        amount: None,
+        recipient: None,
+    };
+    match contract.execute(deps.as_mut(), mock_env(), minter, sweep_msg) {
+        Err(ContractError::PayeesSet {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+
+    // anyone can pay out
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), random, distribute_msg)
+        .unwrap();
+    assert_eq!(
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "artist".to_string(),
+                amount: vec![Coin::new(700_000u128, "uluna")],
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "dev".to_string(),
+                amount: vec![Coin::new(300_000u128, "uluna")],
+            })
+        ],
+        res.messages
+    );
+    let payees: PayeesResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::Payees {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(2, payees.payees.len());
+    assert_eq!(
+        vec![Coin::new(700_000u128, "uluna")],
+        payees.payees[0].received
+    );
+    assert_eq!(
+        vec![Coin::new(300_000u128, "uluna")],
+        payees.payees[1].received
+    );
+
+    // a payee can't be listed twice
+    let minter = mock_info(MINTER, &[]);
+    let new_payees = |dev: &str| ExecuteMsg::SetPayees {
+        payees: vec![
+            PayeeMsg {
+                address: "artist".to_string(),
+                share: Decimal::percent(50),
+            },
+            PayeeMsg {
+                address: dev.to_string(),
+                share: Decimal::percent(50),
+            },
+        ],
+    };
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        minter.clone(),
+        new_payees("artist"),
+    ) {
+        Err(ContractError::DuplicatePayee { address }) => assert_eq!("artist", address),
+        x => panic!("Unexpected {:?}", x),
+    }
+
+    // what is held is paid out under the old shares before the new ones apply
+    // (the mock balance doesn't go down when paid out)
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter, new_payees("newdev"))
+        .unwrap();
+    assert_eq!(
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: "artist".to_string(),
+                amount: vec![Coin::new(700_000u128, "uluna")],
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: "dev".to_string(),
+                amount: vec![Coin::new(300_000u128, "uluna")],
+            })
+        ],
+        res.messages
+    );
+    let payees: PayeesResponse = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::Payees {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(2, payees.payees.len());
+    assert_eq!(1, payees.former_payees.len());
+    assert_eq!("dev", payees.former_payees[0].address);
+    assert_eq!(
+        vec![Coin::new(600_000u128, "uluna")],
+        payees.former_payees[0].received
+    );
+}
+
+#[test]
+fn change_name() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let token_uri = "https://www.merriam-webster.com/dictionary/petrify".to_string();
+    let attributes: Vec<Trait> = vec![
+        Trait {
+            display_type: None,
+            trait_type: "gender".to_string(),
+            value: "male".to_string(),
+        },
+        Trait {
+            display_type: None,
+            trait_type: "name".to_string(),
+            value: "Jim Morrisson".to_string(),
+        },
+    ];
+    let extension = Metadata {
+        token_uri: token_uri.clone(),
+        image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
+        image_data: None,
+        external_url: None,
+        description: None,
+        name: None,
+        attributes: Some(attributes),
+        background_color: None,
+        animation_url: None,
+        youtube_url: None,
+        current_status: None,
+    };
+    let buy_msg = BuyExtension {
+        male_name: "James Dean".to_string(),
+        female_name: "Norma Rae".to_string(),
     };
     let json = serde_json_wasm::to_string(&extension);
     assert!(json.is_ok(), "JSON unpacking failed");
@@ -2194,3 +3486,2310 @@ fn change_name() {
             .is_some()
     );
 }
+
+#[test]
+fn tax_on_payouts() {
+    let mut deps = mock_dependencies(&[Coin::new(1_010_000u128, "uusd")]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    let sweep_msg = ExecuteMsg::Sweep {
+        denom: "uusd".to_string(),
+        amount: None,
+        recipient: None,
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter, sweep_msg)
+        .unwrap();
+    // terra's treasury takes 1% of what is sent, other chains take nothing
+    let expected = if cfg!(feature = "terra") {
+        1_000_000u128
+    } else {
+        1_010_000u128
+    };
+    assert_eq!(
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: MINTER.to_string(),
+            amount: vec![Coin::new(expected, "uusd")],
+        })],
+        res.messages
+    );
+}
+
+#[test]
+fn sweeping() {
+    let mut deps = mock_dependencies(&[
+        Coin::new(1_000_000u128, "uluna"),
+        Coin::new(2_020_000u128, "uusd"),
+    ]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    // part of a balance, to someone else
+    let sweep_msg = ExecuteMsg::Sweep {
+        denom: "uluna".to_string(),
+        amount: Some(Uint128::new(400_000)),
+        recipient: Some("treasury".to_string()),
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), sweep_msg)
+        .unwrap();
+    assert_eq!(
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![Coin::new(400_000u128, "uluna")],
+        })],
+        res.messages
+    );
+
+    // the tax has to fit in the balance too
+    let sweep_msg = ExecuteMsg::Sweep {
+        denom: "uusd".to_string(),
+        amount: Some(Uint128::new(2_020_000)),
+        recipient: None,
+    };
+    let available = if cfg!(feature = "terra") {
+        Uint128::new(2_000_000)
+    } else {
+        Uint128::new(2_020_000)
+    };
+    match contract.execute(deps.as_mut(), mock_env(), minter.clone(), sweep_msg) {
+        Err(ContractError::BalanceTooLow { available: a }) if cfg!(feature = "terra") => {
+            assert_eq!(available, a)
+        }
+        Ok(_) if !cfg!(feature = "terra") => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let sweep_msg = ExecuteMsg::Sweep {
+        denom: "uusd".to_string(),
+        amount: Some(Uint128::MAX),
+        recipient: None,
+    };
+    match contract.execute(deps.as_mut(), mock_env(), minter.clone(), sweep_msg) {
+        Err(ContractError::BalanceTooLow { .. }) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+
+    // everything, every denom
+    let random = mock_info("random", &[]);
+    let sweep_all = ExecuteMsg::SweepAll { recipient: None };
+    match contract.execute(deps.as_mut(), mock_env(), random, sweep_all.clone()) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter, sweep_all)
+        .unwrap();
+    assert_eq!(
+        vec![
+            SubMsg::new(BankMsg::Send {
+                to_address: MINTER.to_string(),
+                amount: vec![Coin::new(1_000_000u128, "uluna")],
+            }),
+            SubMsg::new(BankMsg::Send {
+                to_address: MINTER.to_string(),
+                amount: vec![Coin::new(available.u128(), "uusd")],
+            }),
+        ],
+        res.messages
+    );
+
+    // and it is all in the log
+    let withdrawals: WithdrawalsResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Withdrawals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    let sent: Vec<(String, Coin)> = withdrawals
+        .withdrawals
+        .iter()
+        .map(|w| (w.recipient.to_string(), w.amount.clone()))
+        .collect();
+    let mut expected = vec![
+        ("treasury".to_string(), Coin::new(400_000u128, "uluna")),
+        (MINTER.to_string(), Coin::new(1_000_000u128, "uluna")),
+        (MINTER.to_string(), Coin::new(available.u128(), "uusd")),
+    ];
+    if !cfg!(feature = "terra") {
+        expected.insert(1, (MINTER.to_string(), Coin::new(2_020_000u128, "uusd")));
+    }
+    assert_eq!(expected, sent);
+    let last = withdrawals.withdrawals.last().unwrap();
+    assert_eq!(MINTER, last.sender.as_str());
+    if cfg!(feature = "terra") {
+        assert_eq!(Uint128::new(20_000), last.tax);
+    }
+}
+
+#[test]
+fn random_traits() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    let weighted = |label: &str, weight: u32| NftTraitSummary {
+        label: label.to_string(),
+        value: Decimal::zero(),
+        weight: Some(weight),
+    };
+    let trait_map_msg = ExecuteMsg::SetNftContractTraitInfo {
+        trait_map: vec![
+            (
+                "mood".to_string(),
+                vec![weighted("grumpy", 0), weighted("happy", 1)],
+            ),
+            (
+                "eyes".to_string(),
+                vec![weighted("blue", 3), weighted("green", 1)],
+            ),
+        ],
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), trait_map_msg)
+        .unwrap();
+    // the rarity comes from the weights
+    let trait_map = contract.nft_contract_trait_map(&deps.storage).unwrap();
+    assert_eq!(Decimal::one(), trait_map[0].1[1].value);
+    assert_eq!(Decimal::percent(75), trait_map[1].1[0].value);
+    assert_eq!(Decimal::percent(25), trait_map[1].1[1].value);
+
+    let random_traits: bool = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::RandomTraits {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(!random_traits);
+    // only a commitment to the salt is stored
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        minter.clone(),
+        ExecuteMsg::SetMintSalt {
+            salt_hash: Some("pepper".to_string()),
+        },
+    ) {
+        Err(ContractError::InvalidSaltHash {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    let salt_hash: String = Sha256::digest(b"pepper")
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    let salt_msg = ExecuteMsg::SetMintSalt {
+        salt_hash: Some(salt_hash),
+    };
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        salt_msg.clone(),
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), salt_msg)
+        .unwrap();
+
+    // the signed attributes pick the token id, the contract rolls the rest
+    let extension = Metadata {
+        token_uri: "https://www.merriam-webster.com/dictionary/petrify".to_string(),
+        image: Some("QmRdTSx3v2VFw1xDN8vrYFLdFqjUn8XH8ibaGnsS1GT2mQ".to_string()),
+        attributes: Some(vec![
+            Trait {
+                display_type: None,
+                trait_type: "gender".to_string(),
+                value: "male".to_string(),
+            },
+            Trait {
+                display_type: None,
+                trait_type: "name".to_string(),
+                value: "Jim Morrisson".to_string(),
+            },
+        ]),
+        ..Metadata::default()
+    };
+    let buy_msg = ExecuteMsg::<Extension>::Buy(BuyMsg {
+        signature: "fJCPvlqjmTZxuKT1Uu8FqEmEuvEfNcKv3rdZ267qVFpU3S7AzIXG7ic1d9NxfUdGBdRpwW/yfdoN7nM34dW8LQ==".to_string(),
+        attributes: serde_json_wasm::to_string(&extension).unwrap(),
+        buy_metadata: BuyExtension {
+            male_name: "James Dean".to_string(),
+            female_name: "Norma Rae".to_string(),
+        },
+    });
+    let random = mock_info("random", &[Coin::new(3_000_000u128, "uluna")]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), random, buy_msg)
+        .unwrap();
+    assert!(res.attributes.iter().any(|a| a.key == "seed"));
+
+    // nothing is picked until the salt is revealed
+    let info = contract
+        .nft_info(deps.as_ref(), "James Dean".to_string())
+        .unwrap();
+    assert_eq!(2, info.extension.attributes.unwrap().len());
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        minter.clone(),
+        ExecuteMsg::FreezeToken {
+            token_id: "James Dean".to_string(),
+        },
+    ) {
+        Err(ContractError::RollPending {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        minter.clone(),
+        ExecuteMsg::FreezeMetadata { limit: None },
+    ) {
+        Err(ContractError::RollPending {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    // a rename before the reveal doesn't lose the roll
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[Coin::new(1_000_000u128, "uusd")]),
+            ExecuteMsg::SetTokenNameDescription {
+                token_id: "James Dean".to_string(),
+                name: Some("Jimmy".to_string()),
+                description: None,
+            },
+        )
+        .unwrap();
+    let reveal = |salt: &str| ExecuteMsg::RevealMintSalt {
+        salt: salt.to_string(),
+        limit: None,
+    };
+    match contract.execute(deps.as_mut(), mock_env(), minter.clone(), reveal("salt")) {
+        Err(ContractError::SaltMismatch {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), reveal("pepper"))
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|a| a.key == "revealed" && a.value == "1"));
+    assert!(res
+        .attributes
+        .iter()
+        .any(|a| a.key == "done" && a.value == "true"));
+
+    let info = contract
+        .nft_info(deps.as_ref(), "Jimmy".to_string())
+        .unwrap();
+    let attributes = info.extension.attributes.unwrap();
+    assert_eq!("male", attributes[0].value);
+    assert_eq!("Jimmy", attributes[1].value);
+    assert_eq!(
+        ("mood", "happy"),
+        (
+            attributes[2].trait_type.as_str(),
+            attributes[2].value.as_str()
+        )
+    );
+    assert_eq!("eyes", attributes[3].trait_type);
+    assert!(attributes[3].value == "blue" || attributes[3].value == "green");
+    assert!(contract
+        .token_traits
+        .has(&deps.storage, ("mood", "happy", "Jimmy")));
+
+    // the salt is public now, so buys stop rolling and it can't be revealed again
+    let random_traits: bool = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::RandomTraits {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(!random_traits);
+    match contract.execute(deps.as_mut(), mock_env(), minter, reveal("pepper")) {
+        Err(ContractError::SaltMismatch {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+}
+
+#[test]
+fn trait_statistics() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+
+    for (token_id, gender) in [("Jim", "male"), ("Janis", "female"), ("Ray", "male")] {
+        let token_uri = format!("https://www.merriam-webster.com/dictionary/{}", token_id);
+        let mint_msg = ExecuteMsg::Mint(MintMsg::<Extension> {
+            token_id: token_id.to_string(),
+            owner: "venus".to_string(),
+            token_uri: Some(token_uri.clone()),
+            extension: Metadata {
+                token_uri,
+                image: Some(format!("Qm{}", token_id)),
+                attributes: Some(vec![
+                    Trait {
+                        display_type: None,
+                        trait_type: "gender".to_string(),
+                        value: gender.to_string(),
+                    },
+                    Trait {
+                        display_type: None,
+                        trait_type: "name".to_string(),
+                        value: token_id.to_string(),
+                    },
+                ]),
+                ..Metadata::default()
+            },
+        });
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+    let trait_counts =
+        |deps: Deps, start_after: Option<(String, String)>| -> Vec<(String, String, u64)> {
+            let res: TraitCountsResponse = from_binary(
+                &contract
+                    .query(
+                        deps,
+                        mock_env(),
+                        QueryMsg::TraitCounts {
+                            start_after,
+                            limit: None,
+                        },
+                    )
+                    .unwrap(),
+            )
+            .unwrap();
+            res.counts
+                .into_iter()
+                .map(|c| (c.trait_type, c.value, c.count))
+                .collect()
+        };
+    let count = |t: &str, v: &str, c: u64| (t.to_string(), v.to_string(), c);
+    // in storage order: shorter trait types come first
+    assert_eq!(
+        vec![
+            count("name", "Janis", 1),
+            count("name", "Jim", 1),
+            count("name", "Ray", 1),
+            count("gender", "female", 1),
+            count("gender", "male", 2),
+        ],
+        trait_counts(deps.as_ref(), None)
+    );
+    assert_eq!(
+        vec![
+            count("name", "Jim", 1),
+            count("name", "Ray", 1),
+            count("gender", "female", 1),
+            count("gender", "male", 2),
+        ],
+        trait_counts(
+            deps.as_ref(),
+            Some(("name".to_string(), "Janis".to_string()))
+        )
+    );
+
+    let rarity: TokenRarityResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TokenRarity {
+                    token_id: "Janis".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(Decimal::from_ratio(1u64, 3u64), rarity.traits[0].frequency);
+    assert_eq!(Decimal::from_ratio(6u64, 1u64), rarity.score);
+
+    // renames and burns keep the counters up to date
+    let rename_msg = ExecuteMsg::SetTokenNameDescription {
+        token_id: "Jim".to_string(),
+        name: Some("Morrison".to_string()),
+        description: None,
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            rename_msg,
+        )
+        .unwrap();
+    let burn_msg = ExecuteMsg::Burn {
+        token_id: "Ray".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("venus", &[]), burn_msg)
+        .unwrap();
+    assert_eq!(
+        vec![
+            count("name", "Janis", 1),
+            count("name", "Morrison", 1),
+            count("gender", "female", 1),
+            count("gender", "male", 1),
+        ],
+        trait_counts(deps.as_ref(), None)
+    );
+
+    // and the tokens can be found by trait and status
+    let tokens = |deps: Deps, msg: QueryMsg| -> Vec<String> {
+        let res: TokensResponse =
+            from_binary(&contract.query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.tokens
+    };
+    let by_trait = |trait_type: &str, value: &str| QueryMsg::TokensByTrait {
+        trait_type: trait_type.to_string(),
+        value: value.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    assert_eq!(
+        vec!["Morrison"],
+        tokens(deps.as_ref(), by_trait("gender", "male"))
+    );
+    assert_eq!(
+        vec!["Janis"],
+        tokens(deps.as_ref(), by_trait("gender", "female"))
+    );
+    assert!(tokens(deps.as_ref(), by_trait("name", "Jim")).is_empty());
+
+    let status_msg = ExecuteMsg::SetTokenStatus {
+        token_id: "Janis".to_string(),
+        status: "Piece of my heart".to_string(),
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            status_msg,
+        )
+        .unwrap();
+    let by_status = |status: &str| QueryMsg::TokensByStatus {
+        status: status.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    assert_eq!(
+        vec!["Janis"],
+        tokens(deps.as_ref(), by_status("Piece of my heart"))
+    );
+    assert_eq!(vec!["Morrison"], tokens(deps.as_ref(), by_status("")));
+
+    // a token written before the indexes and serials existed
+    let mut janis = contract.tokens.load(&deps.storage, "Janis").unwrap();
+    contract
+        .serials
+        .remove(&mut deps.storage, U64Key::from(janis.serial));
+    janis.serial = 0;
+    let morrison_serial = contract
+        .tokens
+        .load(&deps.storage, "Morrison")
+        .unwrap()
+        .serial;
+    let next_serial = contract.mint_sequence.load(&deps.storage).unwrap() + 1;
+    contract
+        .remove_traits(
+            &mut deps.storage,
+            "Janis",
+            &janis.extension.get_attributes(),
+        )
+        .unwrap();
+    contract.tokens.remove(&mut deps.storage, "Janis").unwrap();
+    Map::<&str, TokenInfo<Extension>>::new("tokens")
+        .save(&mut deps.storage, "Janis", &janis)
+        .unwrap();
+    assert!(tokens(deps.as_ref(), by_status("Piece of my heart")).is_empty());
+
+    let backfill = |start_after: Option<&str>| ExecuteMsg::BackfillIndexes {
+        start_after: start_after.map(String::from),
+        limit: Some(1),
+    };
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("venus", &[]),
+        backfill(None),
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), backfill(None))
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|a| a.key == "done" && a.value == "false"));
+    assert!(res
+        .attributes
+        .iter()
+        .any(|a| a.key == "last_token_id" && a.value == "Janis"));
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter, backfill(Some("Janis")))
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|a| a.key == "done" && a.value == "true"));
+    assert_eq!(
+        vec!["Janis"],
+        tokens(deps.as_ref(), by_status("Piece of my heart"))
+    );
+    // Janis gets the next serial, Morrison keeps its own
+    let res: SerialToken = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TokenBySerial {
+                    serial: next_serial,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!("Janis", res.token_id);
+    assert_eq!(
+        next_serial,
+        contract.tokens.load(&deps.storage, "Janis").unwrap().serial
+    );
+    assert_eq!(
+        morrison_serial,
+        contract
+            .tokens
+            .load(&deps.storage, "Morrison")
+            .unwrap()
+            .serial
+    );
+    // Morrison was indexed already and isn't counted twice
+    assert_eq!(
+        vec![
+            count("name", "Janis", 1),
+            count("name", "Morrison", 1),
+            count("gender", "female", 1),
+            count("gender", "male", 1),
+        ],
+        trait_counts(deps.as_ref(), None)
+    );
+}
+
+#[test]
+fn tokens_with_info() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    mint_token(deps.as_mut(), &contract, "petrify", "mars");
+    mint_token(deps.as_mut(), &contract, "shrink", "venus");
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            ExecuteMsg::SetImagePrefix {
+                prefix: "https://ipfs.io/ipfs/".to_string(),
+            },
+        )
+        .unwrap();
+
+    let query = |deps: Deps, msg: QueryMsg| -> Vec<(String, String, Option<String>)> {
+        let res: TokensWithInfoResponse<Extension> =
+            from_binary(&contract.query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.tokens
+            .into_iter()
+            .map(|t| (t.token_id, t.info.access.owner, t.info.info.extension.image))
+            .collect()
+    };
+    let token = |id: &str, owner: &str| {
+        (
+            id.to_string(),
+            owner.to_string(),
+            Some(format!("https://ipfs.io/ipfs/Qm{}", id)),
+        )
+    };
+    let range_msg = |start_after: Option<&str>| QueryMsg::AllNftInfoRange {
+        start_after: start_after.map(String::from),
+        limit: None,
+        include_expired: None,
+    };
+    assert_eq!(
+        vec![
+            token("grow", "venus"),
+            token("petrify", "mars"),
+            token("shrink", "venus"),
+        ],
+        query(deps.as_ref(), range_msg(None))
+    );
+    assert_eq!(
+        vec![token("shrink", "venus")],
+        query(
+            deps.as_ref(),
+            QueryMsg::OwnerTokensWithInfo {
+                owner: "venus".to_string(),
+                start_after: Some("grow".to_string()),
+                limit: None,
+                include_expired: None,
+            }
+        )
+    );
+
+    // the page size has its own cap
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("random", &[]),
+        ExecuteMsg::SetInfoMaxLimit { limit: 1 },
+    ) {
+        Err(ContractError::Unauthorized {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        minter.clone(),
+        ExecuteMsg::SetInfoMaxLimit { limit: 0 },
+    ) {
+        Err(ContractError::InvalidLimits {}) => {}
+        x => panic!("Unexpected {:?}", x),
+    }
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::SetInfoMaxLimit { limit: 1 },
+        )
+        .unwrap();
+    assert_eq!(
+        vec![token("petrify", "mars")],
+        query(deps.as_ref(), range_msg(Some("grow")))
+    );
+}
+
+#[test]
+fn paging_both_ways() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER, &[]);
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    mint_token(deps.as_mut(), &contract, "petrify", "venus");
+    mint_token(deps.as_mut(), &contract, "shrink", "venus");
+
+    let page = |deps: Deps, msg: QueryMsg| -> (Vec<String>, Option<Binary>) {
+        let res: TokensPageResponse =
+            from_binary(&contract.query(deps, mock_env(), msg).unwrap()).unwrap();
+        (res.tokens, res.next_cursor)
+    };
+    let all_tokens = |start_before: Option<&str>, order: Option<Order>, cursor: Option<Binary>| {
+        QueryMsg::AllTokens {
+            start_after: None,
+            start_before: start_before.map(String::from),
+            limit: Some(2),
+            order,
+            cursor,
+        }
+    };
+
+    // newest names first, a page at a time
+    let (tokens, cursor) = page(
+        deps.as_ref(),
+        all_tokens(None, Some(Order::Descending), None),
+    );
+    assert_eq!(vec!["shrink", "petrify"], tokens);
+    assert!(cursor.is_some());
+    let (tokens, cursor) = page(
+        deps.as_ref(),
+        all_tokens(None, Some(Order::Descending), cursor),
+    );
+    assert_eq!(vec!["grow"], tokens);
+    assert_eq!(None, cursor);
+
+    let (tokens, _) = page(deps.as_ref(), all_tokens(Some("shrink"), None, None));
+    assert_eq!(vec!["grow", "petrify"], tokens);
+
+    let (tokens, _) = page(
+        deps.as_ref(),
+        QueryMsg::Tokens {
+            owner: "venus".to_string(),
+            start_after: None,
+            start_before: Some("shrink".to_string()),
+            limit: None,
+            order: Some(Order::Descending),
+            cursor: None,
+        },
+    );
+    assert_eq!(vec!["petrify", "grow"], tokens);
+
+    // the admin sets the page sizes
+    match contract.execute(
+        deps.as_mut(),
+        mock_env(),
+        minter.clone(),
+        ExecuteMsg::SetQueryLimits {
+            default_limit: 3,
+            max_limit: 2,
+        },
+    ) {
+        Err(ContractError::InvalidLimits {}) => {}
+        x => assert!(false, "Unexpected {:?}", x),
+    }
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            ExecuteMsg::SetQueryLimits {
+                default_limit: 1,
+                max_limit: 2,
+            },
+        )
+        .unwrap();
+    let img_tokens = |limit: Option<u32>| QueryMsg::AllImgTokens {
+        start_after: None,
+        start_before: None,
+        limit,
+        order: None,
+        cursor: None,
+    };
+    let (tokens, _) = page(deps.as_ref(), img_tokens(None));
+    assert_eq!(vec!["Qmgrow/grow"], tokens);
+    let (tokens, _) = page(deps.as_ref(), img_tokens(Some(30)));
+    assert_eq!(2, tokens.len());
+
+    // operators too
+    for operator in ["apollo", "hermes", "zeus"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("venus", &[]),
+                ExecuteMsg::ApproveAll {
+                    operator: operator.to_string(),
+                    expires: None,
+                },
+            )
+            .unwrap();
+    }
+    let operators = |cursor: Option<Binary>| -> (Vec<String>, Option<Binary>) {
+        let res: OperatorsPageResponse = from_binary(
+            &contract
+                .query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::ApprovedForAll {
+                        owner: "venus".to_string(),
+                        include_expired: None,
+                        start_after: None,
+                        start_before: None,
+                        limit: Some(2),
+                        order: Some(Order::Descending),
+                        cursor,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        (
+            res.operators.into_iter().map(|o| o.spender).collect(),
+            res.next_cursor,
+        )
+    };
+    let (spenders, cursor) = operators(None);
+    assert_eq!(vec!["zeus", "hermes"], spenders);
+    let (spenders, cursor) = operators(cursor);
+    assert_eq!(vec!["apollo"], spenders);
+    assert_eq!(None, cursor);
+}
+
+#[test]
+fn mint_order() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "shrink", "venus");
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    mint_token(deps.as_mut(), &contract, "petrify", "venus");
+
+    let by_mint_order = |deps: Deps, order: Option<Order>| -> Vec<(u64, String)> {
+        let res: SerialTokensResponse = from_binary(
+            &contract
+                .query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::TokensByMintOrder {
+                        start_after: None,
+                        limit: None,
+                        order,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        res.tokens
+            .into_iter()
+            .map(|t| (t.serial, t.token_id))
+            .collect()
+    };
+    let serial = |s: u64, id: &str| (s, id.to_string());
+    assert_eq!(
+        vec![serial(1, "shrink"), serial(2, "grow"), serial(3, "petrify")],
+        by_mint_order(deps.as_ref(), None)
+    );
+
+    // the serial follows the token through a rename, and goes with it on burn
+    let rename_msg = ExecuteMsg::SetTokenNameDescription {
+        token_id: "grow".to_string(),
+        name: Some("bloom".to_string()),
+        description: None,
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            rename_msg,
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::Burn {
+                token_id: "shrink".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        vec![serial(3, "petrify"), serial(2, "bloom")],
+        by_mint_order(deps.as_ref(), Some(Order::Descending))
+    );
+    let token: SerialToken = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TokenBySerial { serial: 2 },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!("bloom", token.token_id);
+    assert!(contract
+        .query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TokenBySerial { serial: 1 },
+        )
+        .is_err());
+}
+
+#[test]
+fn token_uri_lookup() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    mint_token(deps.as_mut(), &contract, "shrink", "venus");
+
+    let dictionary = |id: &str| format!("https://www.merriam-webster.com/dictionary/{}", id);
+    let by_uri = |deps: Deps, token_uri: String| -> String {
+        let res: TokenUri = from_binary(
+            &contract
+                .query(deps, mock_env(), QueryMsg::TokenByUri { token_uri })
+                .unwrap(),
+        )
+        .unwrap();
+        res.token_id
+    };
+    // the lookup gives the token id, not the uri
+    assert_eq!("grow", by_uri(deps.as_ref(), dictionary("grow")));
+
+    // and follows the token through a rename
+    let rename_msg = ExecuteMsg::SetTokenNameDescription {
+        token_id: "grow".to_string(),
+        name: Some("bloom".to_string()),
+        description: None,
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            rename_msg,
+        )
+        .unwrap();
+    assert_eq!("bloom", by_uri(deps.as_ref(), dictionary("grow")));
+
+    let all_uris = |deps: Deps, start_after: Option<String>| -> TokenUrisResponse {
+        from_binary(
+            &contract
+                .query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::AllTokenUris {
+                        start_after,
+                        limit: Some(1),
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap()
+    };
+    let first = all_uris(deps.as_ref(), None);
+    assert_eq!(
+        vec![TokenUri {
+            token_uri: dictionary("grow"),
+            token_id: "bloom".to_string()
+        }],
+        first.tokens
+    );
+    let second = all_uris(deps.as_ref(), Some(dictionary("grow")));
+    assert_eq!(
+        vec![TokenUri {
+            token_uri: dictionary("shrink"),
+            token_id: "shrink".to_string()
+        }],
+        second.tokens
+    );
+
+    // burning frees the uri
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::Burn {
+                token_id: "shrink".to_string(),
+            },
+        )
+        .unwrap();
+    assert!(contract
+        .query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::TokenByUri {
+                token_uri: dictionary("shrink"),
+            },
+        )
+        .is_err());
+}
+
+#[test]
+fn integrity_audit() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    mint_token(deps.as_mut(), &contract, "shrink", "venus");
+
+    let audit = |deps: Deps| -> Vec<IntegrityIssue> {
+        let mut issues = vec![];
+        let mut start_after = None;
+        loop {
+            let res: AuditIntegrityResponse = from_binary(
+                &contract
+                    .query(
+                        deps,
+                        mock_env(),
+                        QueryMsg::AuditIntegrity {
+                            start_after,
+                            limit: Some(2),
+                        },
+                    )
+                    .unwrap(),
+            )
+            .unwrap();
+            issues.extend(res.issues);
+            match res.next {
+                Some(next) => start_after = Some(next),
+                None => return issues,
+            }
+        }
+    };
+    // a transfer starts the change history, the burn takes it away again
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::TransferNft {
+                recipient: "mars".to_string(),
+                token_id: "shrink".to_string(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mars", &[]),
+            ExecuteMsg::Burn {
+                token_id: "shrink".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(Vec::<IntegrityIssue>::new(), audit(deps.as_ref()));
+
+    // break things behind the contract's back
+    let dictionary = |id: &str| format!("https://www.merriam-webster.com/dictionary/{}", id);
+    contract
+        .tokens_uri
+        .remove(&mut deps.storage, &dictionary("grow"))
+        .unwrap();
+    contract
+        .tokens_uri
+        .save(
+            &mut deps.storage,
+            &dictionary("shrink"),
+            &"shrink".to_string(),
+        )
+        .unwrap();
+    contract.token_count.save(&mut deps.storage, &5).unwrap();
+    let issues = audit(deps.as_ref());
+    assert_eq!(
+        vec![
+            IntegrityIssue::TokenUriMissing {
+                token_id: "grow".to_string(),
+                token_uri: dictionary("grow"),
+            },
+            IntegrityIssue::TokenCount {
+                stored: 5,
+                actual: 1
+            },
+            IntegrityIssue::StaleTokenUri {
+                token_uri: dictionary("shrink"),
+                token_id: "shrink".to_string(),
+            },
+        ],
+        issues
+    );
+
+    let repair = ExecuteMsg::Repair {
+        start_after: None,
+        limit: Some(30),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            repair.clone(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), repair)
+        .unwrap();
+    let repaired: AuditIntegrityResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(issues, repaired.issues);
+    assert_eq!(None, repaired.next);
+    assert_eq!(Vec::<IntegrityIssue>::new(), audit(deps.as_ref()));
+    assert_eq!(1, contract.token_count(&deps.storage).unwrap());
+
+    // two tokens with the same token_uri: repair won't pick one
+    mint_token(deps.as_mut(), &contract, "petrify", "venus");
+    let mut token = contract.tokens.load(&deps.storage, "petrify").unwrap();
+    token.token_uri = Some(dictionary("grow"));
+    contract
+        .tokens
+        .save(&mut deps.storage, "petrify", &token)
+        .unwrap();
+    let conflict = IntegrityIssue::TokenUriConflict {
+        token_id: "petrify".to_string(),
+        token_uri: dictionary("grow"),
+        held_by: "grow".to_string(),
+    };
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::Repair {
+                start_after: None,
+                limit: Some(30),
+            },
+        )
+        .unwrap();
+    let repaired: AuditIntegrityResponse = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(
+        vec![
+            conflict.clone(),
+            IntegrityIssue::StaleTokenUri {
+                token_uri: dictionary("petrify"),
+                token_id: "petrify".to_string(),
+            },
+        ],
+        repaired.issues
+    );
+    assert_eq!(vec![conflict], audit(deps.as_ref()));
+    assert_eq!(
+        "grow",
+        contract
+            .tokens_uri
+            .load(&deps.storage, &dictionary("grow"))
+            .unwrap()
+    );
+}
+
+#[test]
+fn metadata_patches() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    mint_token(deps.as_mut(), &contract, "shrink", "venus");
+
+    let update = |patch: MetadataPatch| ExecuteMsg::UpdateTokenMetadata {
+        token_id: "grow".to_string(),
+        patch,
+    };
+    let fix_image = update(MetadataPatch {
+        image: Some("QmGrown".to_string()),
+        attributes: Some(vec![Trait {
+            display_type: None,
+            trait_type: "size".to_string(),
+            value: "large".to_string(),
+        }]),
+        ..MetadataPatch::default()
+    });
+
+    // everything is admin-only to start with
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            fix_image.clone(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info(MINTER, &[]), fix_image)
+        .unwrap();
+    let token = contract.tokens.load(&deps.storage, "grow").unwrap();
+    assert_eq!(Some("QmGrown".to_string()), token.extension.image);
+    assert_eq!(
+        "grow",
+        contract.image_uri.load(&deps.storage, "QmGrown").unwrap()
+    );
+    assert!(contract
+        .image_uri
+        .may_load(&deps.storage, "Qmgrow")
+        .unwrap()
+        .is_none());
+    assert!(contract
+        .token_traits
+        .has(&deps.storage, ("size", "large", "grow")));
+
+    // images stay unique
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            update(MetadataPatch {
+                image: Some("Qmshrink".to_string()),
+                ..MetadataPatch::default()
+            }),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::ImageClaimed {}));
+
+    // the name trait goes with the token id, not even the admin patches it
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            update(MetadataPatch {
+                attributes: Some(vec![Trait {
+                    display_type: None,
+                    trait_type: "name".to_string(),
+                    value: "shrink".to_string(),
+                }]),
+                ..MetadataPatch::default()
+            }),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::ReservedTrait { .. }));
+
+    let access = MetadataFieldAccess {
+        image: FieldAccess::Immutable,
+        external_url: FieldAccess::Owner,
+        description: FieldAccess::Owner,
+        ..MetadataFieldAccess::default()
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetMetadataFieldAccess {
+                access: access.clone(),
+            },
+        )
+        .unwrap();
+    let res: MetadataFieldAccess = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::MetadataFieldAccess {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(access, res);
+
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            update(MetadataPatch {
+                image: Some("QmGrowing".to_string()),
+                ..MetadataPatch::default()
+            }),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::FieldImmutable { .. }));
+
+    let owner_edit = update(MetadataPatch {
+        external_url: Some("https://grow.example".to_string()),
+        ..MetadataPatch::default()
+    });
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mars", &[]),
+            owner_edit.clone(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    // nothing to change isn't a success, whoever sends it
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mars", &[]),
+            update(MetadataPatch::default()),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::EmptyPatch {}));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            owner_edit,
+        )
+        .unwrap();
+    let token = contract.tokens.load(&deps.storage, "grow").unwrap();
+    assert_eq!(
+        Some("https://grow.example".to_string()),
+        token.extension.external_url
+    );
+    assert_eq!(Some("QmGrown".to_string()), token.extension.image);
+
+    // owners pay for descriptions like they do through SetTokenNameDescription
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetChangePricing {
+                kind: ChangeKind::Description,
+                pricing: ChangePricing::Flat {
+                    amount: Uint128::new(500_000),
+                },
+            },
+        )
+        .unwrap();
+    let describe = update(MetadataPatch {
+        description: Some("growing".to_string()),
+        ..MetadataPatch::default()
+    });
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            describe.clone(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Funds {}));
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            describe,
+        )
+        .unwrap();
+    // the overpayment comes back, less tax
+    assert_eq!(1, res.messages.len());
+    let token = contract.tokens.load(&deps.storage, "grow").unwrap();
+    assert_eq!(Some("growing".to_string()), token.extension.description);
+    assert_eq!(
+        1,
+        contract
+            .change_dynamics
+            .load(&deps.storage, "grow")
+            .unwrap()
+            .change_count
+    );
+    // other fields stay free
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            update(MetadataPatch {
+                external_url: Some("https://grown.example".to_string()),
+                ..MetadataPatch::default()
+            }),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Payment(_)));
+}
+
+#[test]
+fn metadata_freeze() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    mint_token(deps.as_mut(), &contract, "shrink", "venus");
+
+    let patch = |token_id: &str| ExecuteMsg::UpdateTokenMetadata {
+        token_id: token_id.to_string(),
+        patch: MetadataPatch {
+            external_url: Some("https://example.com".to_string()),
+            ..MetadataPatch::default()
+        },
+    };
+    let admin = mock_info(MINTER, &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::FreezeToken {
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), patch("grow"))
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Frozen {}));
+    contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), patch("shrink"))
+        .unwrap();
+
+    // a frozen token can't be renamed either, its name is part of the metadata
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            ExecuteMsg::SetTokenNameDescription {
+                token_id: "grow".to_string(),
+                name: Some("bloom".to_string()),
+                description: None,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Frozen {}));
+    let frozen = |deps: Deps, token_id: &str| -> bool {
+        from_binary(
+            &contract
+                .query(
+                    deps,
+                    mock_env(),
+                    QueryMsg::TokenFrozen {
+                        token_id: token_id.to_string(),
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap()
+    };
+    assert!(frozen(deps.as_ref(), "grow"));
+    assert!(!frozen(deps.as_ref(), "shrink"));
+
+    // freezing the collection hashes a page of tokens per call
+    let freeze = ExecuteMsg::FreezeMetadata { limit: Some(1) };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            freeze.clone(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::FreezeMetadata { limit: Some(0) },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::ZeroLimit {}));
+    // the second page is the last, no empty call is needed to finish
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), freeze.clone())
+        .unwrap();
+    assert!(!res.attributes.iter().any(|a| a.key == "metadata_hash"));
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), freeze.clone())
+        .unwrap();
+    assert!(res.attributes.iter().any(|a| a.key == "metadata_hash"));
+    let status: Option<MetadataFreeze> = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::ProvenanceHash {})
+            .unwrap(),
+    )
+    .unwrap();
+    let status = status.unwrap();
+    assert!(status.done);
+    assert_eq!(2, status.tokens_hashed);
+    let mut expected = Sha256::digest(b"").to_vec();
+    for token_id in ["grow", "shrink"] {
+        let token = contract.tokens.load(&deps.storage, token_id).unwrap();
+        expected = Sha256::new()
+            .chain(&expected)
+            .chain(token_id.as_bytes())
+            .chain(token.token_uri.unwrap().as_bytes())
+            .chain(cosmwasm_std::to_vec(&token.extension).unwrap())
+            .result()
+            .to_vec();
+    }
+    assert_eq!(Binary::from(expected), status.hash);
+
+    assert!(frozen(deps.as_ref(), "shrink"));
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), patch("shrink"))
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Frozen {}));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::SetImagePrefix {
+                prefix: "https://example.com/".to_string(),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Frozen {}));
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), freeze)
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Frozen {}));
+
+    // nothing that goes into the hash can change once it is taken
+    let changes = [
+        (
+            ExecuteMsg::SetTokenNameDescription {
+                token_id: "shrink".to_string(),
+                name: None,
+                description: Some("smaller".to_string()),
+            },
+            vec![Coin::new(1_000_000u128, "uusd")],
+        ),
+        (
+            ExecuteMsg::SetTokenStatus {
+                token_id: "shrink".to_string(),
+                status: "asleep".to_string(),
+            },
+            vec![],
+        ),
+    ];
+    for (change, funds) in changes {
+        let err = contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("venus", &funds),
+                change,
+            )
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Frozen {}));
+    }
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::ModerateToken {
+                token_id: "shrink".to_string(),
+                name: None,
+                status: Some("".to_string()),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Frozen {}));
+    let mint = ExecuteMsg::Mint(MintMsg::<Extension> {
+        token_id: "petrify".to_string(),
+        owner: "venus".to_string(),
+        token_uri: Some("https://example.com/petrify".to_string()),
+        extension: Metadata::default(),
+    });
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), admin, mint)
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Frozen {}));
+}
+
+#[test]
+fn provenance() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let admin = mock_info(MINTER, &[]);
+    let at = |height: u64| {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    };
+    let height = mock_env().block.height;
+
+    let images = ["Qmgrow", "Qmshrink", "Qmpetrify"].concat();
+    let provenance_hash = hex_upper(&Sha256::digest(images.as_bytes()));
+    let set = |provenance_hash: &str| ExecuteMsg::SetProvenance {
+        provenance_hash: provenance_hash.to_string(),
+        reveal_height: Some(height + 100),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), set("Qmgrow"))
+        .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidProvenance {}));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            set(&provenance_hash),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            set(&provenance_hash),
+        )
+        .unwrap();
+
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            set(&provenance_hash),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::ProvenanceLocked {}));
+
+    // not even the admin can pick the starting index before the sell out or the reveal height
+    let finalize = ExecuteMsg::FinalizeStartingIndex {};
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), admin.clone(), finalize.clone())
+        .unwrap_err();
+    assert!(matches!(err, ContractError::StartingIndexNotDue {}));
+    // the first call records the block, the index comes from a later one
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            at(height + 100),
+            mock_info("venus", &[]),
+            finalize.clone(),
+        )
+        .unwrap();
+    assert!(!res.attributes.iter().any(|a| a.key == "starting_index"));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            at(height + 100),
+            mock_info("venus", &[]),
+            finalize.clone(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::StartingIndexTooSoon {}));
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            at(height + 101),
+            mock_info("venus", &[]),
+            finalize.clone(),
+        )
+        .unwrap();
+    assert!(res.attributes.iter().any(|a| a.key == "starting_index"));
+    let err = contract
+        .execute(deps.as_mut(), at(height + 102), admin, finalize.clone())
+        .unwrap_err();
+    assert!(matches!(err, ContractError::StartingIndexSet {}));
+
+    let info: Option<Provenance> = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::ProvenanceInfo {})
+            .unwrap(),
+    )
+    .unwrap();
+    let info = info.unwrap();
+    assert_eq!(provenance_hash.to_ascii_lowercase(), info.provenance_hash);
+    assert_eq!(3, info.collection_size);
+    assert!(info.starting_index.unwrap() < 3);
+    assert_eq!(Some(height + 100), info.starting_index_block);
+
+    // without a reveal height it waits for the sell out, counting burns
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetProvenance {
+                provenance_hash: provenance_hash.clone(),
+                reveal_height: None,
+            },
+        )
+        .unwrap();
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    mint_token(deps.as_mut(), &contract, "shrink", "venus");
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            at(height + 1000),
+            mock_info("venus", &[]),
+            finalize.clone(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::StartingIndexNotDue {}));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::Burn {
+                token_id: "grow".to_string(),
+            },
+        )
+        .unwrap();
+    // the burn took one off max_issuance, so this is the last one
+    mint_token(deps.as_mut(), &contract, "petrify", "venus");
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("venus", &[]), finalize)
+        .unwrap();
+
+    // tokens sold before mint_sequence existed count as sales too
+    contract.mint_sequence.remove(&mut deps.storage);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetProvenance {
+                provenance_hash,
+                reveal_height: None,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::ProvenanceLocked {}));
+}
+
+fn hex_upper(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+#[test]
+fn uri_prefixes() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    let extension = Metadata {
+        token_uri: "grow".to_string(),
+        image: Some("Qmgrow".to_string()),
+        animation_url: Some("Qmgrowing".to_string()),
+        external_url: Some("https://grow.example".to_string()),
+        ..Metadata::default()
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::Mint(MintMsg::<Extension> {
+                token_id: "grow".to_string(),
+                owner: "venus".to_string(),
+                token_uri: Some("grow".to_string()),
+                extension,
+            }),
+        )
+        .unwrap();
+
+    // contracts that only ever set the image prefix keep it once migrated
+    contract
+        .image_prefix
+        .save(&mut deps.storage, &"ipfs://".to_string())
+        .unwrap();
+    let prefixes = |deps: Deps| -> UriPrefixes {
+        from_binary(
+            &contract
+                .query(deps, mock_env(), QueryMsg::ImagePrefix {})
+                .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!("", prefixes(deps.as_ref()).image);
+    contract
+        .migrate(deps.as_mut(), mock_env(), MigrateMsg {})
+        .unwrap();
+    assert_eq!("ipfs://", prefixes(deps.as_ref()).image);
+    assert!(contract
+        .image_prefix
+        .may_load(&deps.storage)
+        .unwrap()
+        .is_none());
+
+    for (field, prefix) in [
+        (UriField::Image, "https://images.example/"),
+        (UriField::AnimationUrl, "https://video.example/"),
+        (UriField::ExternalUrl, "https://links.example/"),
+    ] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(MINTER, &[]),
+                ExecuteMsg::SetUriPrefix {
+                    field,
+                    prefix: prefix.to_string(),
+                },
+            )
+            .unwrap();
+    }
+    assert_eq!(
+        UriPrefixes {
+            image: "https://images.example/".to_string(),
+            animation_url: "https://video.example/".to_string(),
+            external_url: "https://links.example/".to_string(),
+        },
+        prefixes(deps.as_ref())
+    );
+
+    let check = |extension: Metadata| {
+        assert_eq!(
+            Some("https://images.example/Qmgrow".to_string()),
+            extension.image
+        );
+        assert_eq!(
+            Some("https://video.example/Qmgrowing".to_string()),
+            extension.animation_url
+        );
+        // already a full uri
+        assert_eq!(
+            Some("https://grow.example".to_string()),
+            extension.external_url
+        );
+    };
+    check(
+        contract
+            .nft_info(deps.as_ref(), "grow".to_string())
+            .unwrap()
+            .extension,
+    );
+    check(
+        contract
+            .all_nft_info(deps.as_ref(), mock_env(), "grow".to_string(), false)
+            .unwrap()
+            .info
+            .extension,
+    );
+    let res: NftInfoResponse<Extension> = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ImageInfo {
+                    img_uri: "Qmgrow".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    check(res.extension);
+}
+
+#[test]
+fn uri_normalization() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+
+    let mint = |deps: DepsMut, token_id: &str, token_uri: &str, image: &str| {
+        contract.execute(
+            deps,
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::Mint(MintMsg::<Extension> {
+                token_id: token_id.to_string(),
+                owner: "venus".to_string(),
+                token_uri: Some(token_uri.to_string()),
+                extension: Metadata {
+                    image: Some(image.to_string()),
+                    token_uri: token_uri.to_string(),
+                    ..Metadata::default()
+                },
+            }),
+        )
+    };
+    mint(
+        deps.as_mut(),
+        "grow",
+        "ipfs://ipfs/QmGrowMeta/grow.json",
+        " ipfs://QmGrow/ ",
+    )
+    .unwrap();
+    // stored in one spelling, indexed by the bare CID
+    let token = contract.tokens.load(&deps.storage, "grow").unwrap();
+    assert_eq!(
+        Some("ipfs://QmGrowMeta/grow.json".to_string()),
+        token.token_uri
+    );
+    assert_eq!("ipfs://QmGrowMeta/grow.json", token.extension.token_uri);
+    assert_eq!(Some("ipfs://QmGrow".to_string()), token.extension.image);
+    assert_eq!(
+        "grow",
+        contract.image_uri.load(&deps.storage, "QmGrow").unwrap()
+    );
+    let res: TokenUri = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::TokenByUri {
+                    token_uri: "https://ipfs.io/ipfs/QmGrowMeta/grow.json".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!("grow", res.token_id);
+    let res: NftInfoResponse<Extension> = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ImageInfo {
+                    img_uri: "https://QmGrow.ipfs.dweb.link".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(Some("ipfs://QmGrow".to_string()), res.extension.image);
+    // and go out through the image gateway once there is one
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetUriPrefix {
+                field: UriField::Image,
+                prefix: "https://ipfs.io/ipfs/".to_string(),
+            },
+        )
+        .unwrap();
+    let res = contract
+        .nft_info(deps.as_ref(), "grow".to_string())
+        .unwrap();
+    assert_eq!(
+        Some("https://ipfs.io/ipfs/QmGrow".to_string()),
+        res.extension.image
+    );
+
+    // entries from before normalization are moved over by a repair
+    contract
+        .tokens_uri
+        .remove(&mut deps.storage, "QmGrowMeta/grow.json")
+        .unwrap();
+    contract
+        .tokens_uri
+        .save(
+            &mut deps.storage,
+            "ipfs://ipfs/QmGrowMeta/grow.json",
+            &"grow".to_string(),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::Repair {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+    let res: TokenUrisResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::AllTokenUris {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        vec![TokenUri {
+            token_uri: "QmGrowMeta/grow.json".to_string(),
+            token_id: "grow".to_string(),
+        }],
+        res.tokens
+    );
+
+    // the same CID through a gateway is the same image
+    for image in [
+        "https://ipfs.io/ipfs/QmGrow",
+        "https://QmGrow.ipfs.dweb.link/",
+        "ipfs://QmGrow?filename=grow.png",
+    ] {
+        let err = mint(deps.as_mut(), "shrink", "QmShrinkMeta", image).unwrap_err();
+        assert!(matches!(err, ContractError::ImageClaimed {}), "{}", image);
+    }
+    let err = mint(deps.as_mut(), "shrink", "  ", "QmShrink").unwrap_err();
+    assert!(matches!(err, ContractError::EmptyUri {}));
+    let err = mint(deps.as_mut(), "shrink", &"Qm".repeat(257), "QmShrink").unwrap_err();
+    assert!(matches!(err, ContractError::UriTooLong { max_length: 512 }));
+
+    let policy = UriPolicy {
+        max_length: 100,
+        allowed_schemes: Some(vec!["https".to_string(), "ipfs".to_string()]),
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetUriPolicy {
+                policy: policy.clone(),
+            },
+        )
+        .unwrap();
+    let res: UriPolicy = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::UriPolicy {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(policy, res);
+    let err = mint(
+        deps.as_mut(),
+        "shrink",
+        "http://example.com/shrink.json",
+        "QmShrink",
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::SchemeNotAllowed { .. }));
+    mint(
+        deps.as_mut(),
+        "shrink",
+        "https://example.com/shrink.json",
+        "ipfs://QmShrink",
+    )
+    .unwrap();
+}
+
+#[test]
+fn content_policy() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+
+    let status = |deps: DepsMut, status: &str| {
+        contract.execute(
+            deps,
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::SetTokenStatus {
+                token_id: "grow".to_string(),
+                status: status.to_string(),
+            },
+        )
+    };
+    let err = status(deps.as_mut(), "on loan\u{1b}[31m").unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::InvalidCharacter {
+            character: '\u{1b}',
+            ..
+        }
+    ));
+    let err = status(deps.as_mut(), &"z".repeat(129)).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::StatusTooLong { max_length: 128 }
+    ));
+    status(deps.as_mut(), "on loan").unwrap();
+
+    let rename = |deps: DepsMut, name: &str, description: Option<&str>| {
+        contract.execute(
+            deps,
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            ExecuteMsg::SetTokenNameDescription {
+                token_id: "grow".to_string(),
+                name: Some(name.to_string()),
+                description: description.map(String::from),
+            },
+        )
+    };
+    // names that look like another, or like nothing, are out by default
+    let err = rename(deps.as_mut(), "gr\u{200b}ow", None).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::InvalidCharacter {
+            character: '\u{200b}',
+            ..
+        }
+    ));
+    let err = rename(deps.as_mut(), "\u{202e}worg", None).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidCharacter { .. }));
+    let err = rename(deps.as_mut(), "grow ", None).unwrap_err();
+    assert!(matches!(err, ContractError::NameWhitespace {}));
+    let err = rename(deps.as_mut(), "   ", None).unwrap_err();
+    assert!(matches!(err, ContractError::NameWhitespace {}));
+
+    let policy = ContentPolicy {
+        max_name_length: 12,
+        max_description_length: 20,
+        max_status_length: 128,
+        name_chars: Some(vec![CharClass::AsciiLetters, CharClass::Space]),
+        reserved_words: vec!["admin".to_string()],
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::SetContentPolicy {
+                policy: policy.clone(),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER, &[]),
+            ExecuteMsg::SetContentPolicy {
+                policy: policy.clone(),
+            },
+        )
+        .unwrap();
+    let res: ContentPolicy = from_binary(
+        &contract
+            .query(deps.as_ref(), mock_env(), QueryMsg::ContentPolicy {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(policy, res);
+
+    let err = rename(deps.as_mut(), "Peep ADMIN", None).unwrap_err();
+    assert!(matches!(err, ContractError::ReservedWord { .. }));
+    let err = rename(deps.as_mut(), "Peep 2", None).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::InvalidCharacter { character: '2', .. }
+    ));
+    let err = rename(deps.as_mut(), "Peep the Great", None).unwrap_err();
+    assert!(matches!(err, ContractError::NameTooLong { max_length: 12 }));
+    let err = rename(deps.as_mut(), "Peep", Some("a very long description")).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::DescriptionTooLong { max_length: 20 }
+    ));
+    rename(deps.as_mut(), "Peep", Some("a peep")).unwrap();
+    contract.tokens.load(&deps.storage, "Peep").unwrap();
+}
+
+#[test]
+fn moderation() {
+    let mut deps = mock_dependencies(&[]);
+    let contract = setup_contract(deps.as_mut());
+    mint_token(deps.as_mut(), &contract, "grow", "venus");
+    let admin = mock_info(MINTER, &[]);
+
+    let names = vec!["Voldemort".to_string(), "Sauron".to_string()];
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            ExecuteMsg::AddBlockedNames {
+                names: names.clone(),
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::AddBlockedNames { names },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::RemoveBlockedNames {
+                names: vec!["SAURON".to_string()],
+            },
+        )
+        .unwrap();
+    let res: BlockedNamesResponse = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::BlockedNames {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(vec!["voldemort".to_string()], res.names);
+
+    let rename = |name: &str| ExecuteMsg::SetTokenNameDescription {
+        token_id: "grow".to_string(),
+        name: Some(name.to_string()),
+        description: None,
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            rename("VOLDEMORT"),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::NameBlocked {}));
+    // only the whole name is matched
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            rename("Voldemort Jr"),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[Coin::new(1_000_000u128, "uusd")]),
+            ExecuteMsg::SetTokenNameDescription {
+                token_id: "Voldemort Jr".to_string(),
+                name: Some("Sauron".to_string()),
+                description: None,
+            },
+        )
+        .unwrap();
+
+    // the admin steps in, for free
+    let moderate = ExecuteMsg::ModerateToken {
+        token_id: "Sauron".to_string(),
+        name: Some("Frodo".to_string()),
+        status: Some("moderated".to_string()),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            moderate.clone(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::Unauthorized {}));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::ModerateToken {
+                token_id: "Sauron".to_string(),
+                name: None,
+                status: None,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::NothingToModerate {}));
+    // the admin is held to the blocklist too
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            admin.clone(),
+            ExecuteMsg::ModerateToken {
+                token_id: "Sauron".to_string(),
+                name: Some("Voldemort".to_string()),
+                status: None,
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, ContractError::NameBlocked {}));
+    contract
+        .execute(deps.as_mut(), mock_env(), admin, moderate)
+        .unwrap();
+    let token = contract.tokens.load(&deps.storage, "Frodo").unwrap();
+    assert_eq!("venus", token.owner.as_str());
+    assert_eq!(
+        Some("moderated".to_string()),
+        token.extension.current_status
+    );
+    assert!(contract
+        .tokens
+        .may_load(&deps.storage, "Sauron")
+        .unwrap()
+        .is_none());
+    let dynamics: ChangeDynamics = from_binary(
+        &contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ChangeDynamics {
+                    token_id: "Frodo".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!("Frodo", dynamics.token_id);
+    assert_eq!(2, dynamics.change_count);
+    assert_eq!(1, dynamics.moderation_count);
+}
@@ -1,5 +1,6 @@
 //use cosmwasm_crypto::CryptoError;
-use cosmwasm_std::{StdError, VerificationError};
+use cosmwasm_std::{StdError, Uint128, VerificationError};
+use cw0::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -11,10 +12,26 @@ pub enum ContractError {
     Unauthorized {},
     #[error("Funds Missing or insufficient")]
     Funds {},
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
     #[error("No Balance in that denomination")]
     NoFunds {},
     #[error("Not enough funds to cover the tax")]
     FundsTooSmall {},
+    #[error("Balance too low, at most {available} can be sent")]
+    BalanceTooLow { available: Uint128 },
+    #[error("Limits must be at least 1, and the default no more than the max")]
+    InvalidLimits {},
+    #[error("Limit must be at least 1")]
+    ZeroLimit {},
+    #[error("Payee shares must add up to 1")]
+    InvalidShares {},
+    #[error("No payees have been set")]
+    NoPayees {},
+    #[error("{address} is listed as a payee more than once")]
+    DuplicatePayee { address: String },
+    #[error("Revenue goes to the payees, use distribute")]
+    PayeesSet {},
     #[error("Signature doesn't match")]
     BadSignature {},
 
@@ -26,11 +43,77 @@ pub enum ContractError {
     ImageClaimed {},
     #[error("image missing")]
     ImageMissing {},
+    #[error("Provenance hash must be a hex sha256")]
+    InvalidProvenance {},
+    #[error("Provenance can't change once sales have started")]
+    ProvenanceLocked {},
+    #[error("No provenance has been set")]
+    ProvenanceMissing {},
+    #[error("Starting index has already been set")]
+    StartingIndexSet {},
+    #[error("Starting index can't be set before the sell out or the reveal height")]
+    StartingIndexNotDue {},
+    #[error("Starting index is set in a later block than it was requested in")]
+    StartingIndexTooSoon {},
+    #[error("Metadata is frozen")]
+    Frozen {},
+    #[error("The {trait_type} trait can't be changed by a metadata patch")]
+    ReservedTrait { trait_type: String },
+    #[error("Salt hash must be a hex sha256")]
+    InvalidSaltHash {},
+    #[error("Salt doesn't match the one committed to")]
+    SaltMismatch {},
+    #[error("Traits are waiting on the mint salt")]
+    RollPending {},
+    #[error("Name can't start or end with whitespace")]
+    NameWhitespace {},
+    #[error("Name is longer than {max_length}")]
+    NameTooLong { max_length: u32 },
+    #[error("Description is longer than {max_length}")]
+    DescriptionTooLong { max_length: u32 },
+    #[error("Status is longer than {max_length}")]
+    StatusTooLong { max_length: u32 },
+    #[error("{field} can't contain {character:?}")]
+    InvalidCharacter { field: String, character: char },
+    #[error("That name is blocked")]
+    NameBlocked {},
+    #[error("Moderation needs a new name or a status")]
+    NothingToModerate {},
+    #[error("Metadata patch has nothing to change")]
+    EmptyPatch {},
+    #[error("{word} is reserved")]
+    ReservedWord { word: String },
+    #[error("uri can't be empty")]
+    EmptyUri {},
+    #[error("uri is longer than {max_length}")]
+    UriTooLong { max_length: u32 },
+    #[error("uri scheme {scheme} isn't allowed")]
+    SchemeNotAllowed { scheme: String },
+    #[error("{field} can't be changed")]
+    FieldImmutable { field: String },
     #[error("Maximum amount of tokens already issued")]
     MaxIssued {},
 
     #[error("Cannot set approval that is already expired")]
     Expired {},
+    #[error("Token is locked")]
+    Locked {},
+    #[error("Token owners have to set when their lock ends")]
+    LockNeedsExpiry {},
+    #[error("A lock can only be extended by an expiry of the same kind (height or time)")]
+    LockExpiryMismatch {},
+    #[error("Token is staked")]
+    Staked {},
+    #[error("Token is not staked")]
+    NotStaked {},
+    #[error("Stake duration is too long")]
+    StakeTooLong {},
+    #[error("Token can't be unstaked until {unlocks_at}")]
+    StakeLocked { unlocks_at: u64 },
+    #[error("Receiving contract failed: {error}")]
+    SendFailed { error: String },
+    #[error("No send is waiting on reply {id}")]
+    UnknownReply { id: u64 },
     // #[error(transparent)]
     //CryptoError(#[from] cosmwasm_crypto::CryptoError),
     //#[error(transparent)]
@@ -1,22 +1,35 @@
 use cosmwasm_std::{
-    BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, QuerierWrapper,
-    Response, StdError, StdResult, Uint128,
+    to_binary, to_vec, Addr, BankMsg, Binary, Coin, ContractResult, Decimal, Deps, DepsMut, Empty,
+    Env, MessageInfo, Order, QuerierWrapper, Reply, ReplyOn, Response, StdError, StdResult,
+    Storage, SubMsg, Uint128,
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
 
 use crate::error::ContractError;
-use crate::extension::{MetaDataPersonalization, MetaPersonalize};
+use crate::extension::{
+    MetaDataPersonalization, MetaPersonalize, MetadataField, MetadataPatch, Trait, UriField,
+};
+use cw0::{may_pay, nonpayable};
 use cw2::set_contract_version;
 use cw721::{ContractInfoResponse, CustomMsg, Cw721Execute, Cw721ReceiveMsg, Expiration};
-use terra_cosmwasm::TerraQuerier;
-use terraswap::querier::query_balance;
+use cw_storage_plus::{Bound, U64Key};
 
-use crate::msg::{BuyMsg, ExecuteMsg, InstantiateMsg, MintMsg};
+use crate::content::{check_description, check_name, check_status};
+use crate::msg::{
+    AuditCursor, AuditIntegrityResponse, BuyMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, MintMsg,
+    PayeeMsg,
+};
 use crate::state::{
-    Approval, ChangeDynamics, Cw721Contract, NftListing, NftTraitSummary, TokenInfo,
+    Approval, ChangeDynamics, ChangeKind, ChangePricing, ContentPolicy, Cw721Contract, FieldAccess,
+    MetadataFieldAccess, MetadataFreeze, NftListing, NftTraitSummary, Payee, PendingSend,
+    Provenance, QueryLimits, SendErrorPolicy, SendRecord, Stake, TokenInfo, TokenUser, UriPolicy,
+    Withdrawal,
 };
+use crate::tax::{compute_tax, tax_on};
+use crate::uri::{normalize_uri, uri_key};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:terra-peep721";
@@ -25,7 +38,6 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const ECDSA_COMPRESSED_PUBKEY_LEN: usize = 33;
 /// Length of a serialized uncompressed public key
 const ECDSA_UNCOMPRESSED_PUBKEY_LEN: usize = 65;
-static DECIMAL_FRACTION: Uint128 = Uint128::new(1_000_000_000_000_000_000u128);
 
 impl<'a, T, C> Cw721Contract<'a, T, C>
 where
@@ -68,8 +80,40 @@ where
         self.change_multiplier
             .save(deps.storage, &msg.change_multiplier)?;
         self.max_issuance.save(deps.storage, &msg.max_issuance)?;
+        if let Some(provenance_hash) = msg.provenance_hash {
+            self.provenance.save(
+                deps.storage,
+                &Provenance {
+                    provenance_hash: Self::check_provenance_hash(&provenance_hash)
+                        .map_err(|e| StdError::generic_err(e.to_string()))?,
+                    collection_size: msg.max_issuance,
+                    starting_index: None,
+                    starting_index_block: None,
+                    reveal_height: None,
+                },
+            )?;
+        }
+        Ok(Response::default())
+    }
+
+    pub fn migrate(&self, deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response<C>> {
+        // the image prefix now lives with the other uri prefixes
+        if let Some(prefix) = self.image_prefix.may_load(deps.storage)? {
+            let image = UriField::Image.as_str();
+            if !self.uri_prefixes.has(deps.storage, image) {
+                self.uri_prefixes.save(deps.storage, image, &prefix)?;
+            }
+            self.image_prefix.remove(deps.storage);
+        }
         Ok(Response::default())
     }
+    /// the hash lower-cased, if it is a hex sha256
+    fn check_provenance_hash(provenance_hash: &str) -> Result<String, ContractError> {
+        if provenance_hash.len() != 64 || !provenance_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ContractError::InvalidProvenance {});
+        }
+        Ok(provenance_hash.to_ascii_lowercase())
+    }
     fn check_pubkey(data: &[u8]) -> Result<(), ContractError> {
         let ok = match data.first() {
             Some(0x02) | Some(0x03) => data.len() == ECDSA_COMPRESSED_PUBKEY_LEN,
@@ -90,6 +134,13 @@ where
         info: MessageInfo,
         msg: ExecuteMsg<T>,
     ) -> Result<Response<C>, ContractError> {
+        match msg {
+            // these take payment, and refund anything over the price
+            ExecuteMsg::Buy(_)
+            | ExecuteMsg::SetTokenNameDescription { .. }
+            | ExecuteMsg::UpdateTokenMetadata { .. } => {}
+            _ => nonpayable(&info)?,
+        }
         match msg {
             ExecuteMsg::Mint(msg) => self.mint(deps, env, info, msg),
             ExecuteMsg::Burn { token_id } => self.burn(deps, env, info, token_id),
@@ -113,7 +164,15 @@ where
                 contract,
                 token_id,
                 msg,
-            } => self.send_nft(deps, env, info, contract, token_id, msg),
+                reply_on,
+            } => match reply_on {
+                None | Some(ReplyOn::Never) => {
+                    self.send_nft(deps, env, info, contract, token_id, msg)
+                }
+                Some(reply_on) => {
+                    self.send_nft_tracked(deps, env, info, contract, token_id, msg, reply_on)
+                }
+            },
             ExecuteMsg::Buy(msg) => self.buy(deps, env, info, msg),
             ExecuteMsg::SetPublicKey { public_key } => {
                 self.set_public_key(deps, env, info, public_key)
@@ -128,7 +187,66 @@ where
             ExecuteMsg::SetChangeTimesMultiplier { change_multiplier } => {
                 self.set_change_multiplier(deps, env, info, change_multiplier)
             }
-            ExecuteMsg::SetImagePrefix { prefix } => self.set_image_prefix(deps, env, info, prefix),
+            ExecuteMsg::SetImagePrefix { prefix } => {
+                self.set_uri_prefix(deps, env, info, UriField::Image, prefix)
+            }
+            ExecuteMsg::ModerateToken {
+                token_id,
+                name,
+                status,
+            } => self.moderate_token(deps, env, info, token_id, name, status),
+            ExecuteMsg::AddBlockedNames { names } => self.add_blocked_names(deps, env, info, names),
+            ExecuteMsg::RemoveBlockedNames { names } => {
+                self.remove_blocked_names(deps, env, info, names)
+            }
+            ExecuteMsg::SetContentPolicy { policy } => {
+                self.set_content_policy(deps, env, info, policy)
+            }
+            ExecuteMsg::SetUriPolicy { policy } => self.set_uri_policy(deps, env, info, policy),
+            ExecuteMsg::SetUriPrefix { field, prefix } => {
+                self.set_uri_prefix(deps, env, info, field, prefix)
+            }
+            ExecuteMsg::SetSoulbound { soulbound } => {
+                self.set_soulbound(deps, env, info, soulbound)
+            }
+            ExecuteMsg::LockToken { token_id, expires } => {
+                self.lock_token(deps, env, info, token_id, expires)
+            }
+            ExecuteMsg::UnlockToken { token_id } => self.unlock_token(deps, env, info, token_id),
+            ExecuteMsg::SetUser {
+                token_id,
+                user,
+                expires,
+            } => self.set_user(deps, env, info, token_id, user, expires),
+            ExecuteMsg::AddMetadataDelegate { delegate, token_id } => {
+                self.update_metadata_delegate(deps, env, info, delegate, token_id, true)
+            }
+            ExecuteMsg::RemoveMetadataDelegate { delegate, token_id } => {
+                self.update_metadata_delegate(deps, env, info, delegate, token_id, false)
+            }
+            ExecuteMsg::Stake { token_id, duration } => {
+                self.stake(deps, env, info, token_id, duration)
+            }
+            ExecuteMsg::Unstake { token_id } => self.unstake(deps, env, info, token_id),
+            ExecuteMsg::UpdateTokenMetadata { token_id, patch } => {
+                self.update_token_metadata(deps, env, info, token_id, patch)
+            }
+            ExecuteMsg::SetProvenance {
+                provenance_hash,
+                reveal_height,
+            } => self.set_provenance(deps, env, info, provenance_hash, reveal_height),
+            ExecuteMsg::FinalizeStartingIndex {} => self.finalize_starting_index(deps, env, info),
+            ExecuteMsg::FreezeMetadata { limit } => self.freeze_metadata(deps, env, info, limit),
+            ExecuteMsg::FreezeToken { token_id } => self.freeze_token(deps, env, info, token_id),
+            ExecuteMsg::SetMetadataFieldAccess { access } => {
+                self.set_metadata_field_access(deps, env, info, access)
+            }
+            ExecuteMsg::SetSendErrorPolicy { policy } => {
+                self.set_send_error_policy(deps, env, info, policy)
+            }
+            ExecuteMsg::SetChangePricing { kind, pricing } => {
+                self.set_change_pricing(deps, env, info, kind, pricing)
+            }
             ExecuteMsg::SetTokenStatus { token_id, status } => {
                 self.set_status(deps, env, info, token_id, status)
             }
@@ -165,7 +283,31 @@ where
             ExecuteMsg::SetNftContractKeybaseVerification { message } => {
                 self.set_nft_keybase_verification(deps, env, info, message)
             }
-            ExecuteMsg::Sweep { denom } => self.sweep(deps, env, info, denom),
+            ExecuteMsg::Sweep {
+                denom,
+                amount,
+                recipient,
+            } => self.sweep(deps, env, info, denom, amount, recipient),
+            ExecuteMsg::SetMintSalt { salt_hash } => self.set_mint_salt(deps, env, info, salt_hash),
+            ExecuteMsg::RevealMintSalt { salt, limit } => {
+                self.reveal_mint_salt(deps, env, info, salt, limit)
+            }
+            ExecuteMsg::SetInfoMaxLimit { limit } => {
+                self.set_info_max_limit(deps, env, info, limit)
+            }
+            ExecuteMsg::SetQueryLimits {
+                default_limit,
+                max_limit,
+            } => self.set_query_limits(deps, env, info, default_limit, max_limit),
+            ExecuteMsg::Repair { start_after, limit } => {
+                self.repair_page(deps, env, info, start_after, limit)
+            }
+            ExecuteMsg::BackfillIndexes { start_after, limit } => {
+                self.backfill_indexes(deps, env, info, start_after, limit)
+            }
+            ExecuteMsg::SweepAll { recipient } => self.sweep_all(deps, env, info, recipient),
+            ExecuteMsg::SetPayees { payees } => self.set_payees(deps, env, info, payees),
+            ExecuteMsg::Distribute { denom } => self.distribute(deps, env, info, denom),
         }
     }
 }
@@ -181,7 +323,7 @@ where
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        msg: MintMsg<T>,
+        mut msg: MintMsg<T>,
     ) -> Result<Response<C>, ContractError> {
         let minter = self.minter.load(deps.storage)?;
         let max_issuance = self.max_issuance.load(deps.storage)?;
@@ -193,12 +335,35 @@ where
         if count >= max_issuance {
             return Err(ContractError::MaxIssued {});
         }
+        // a frozen collection can't grow
+        self.check_not_frozen(deps.storage, None)?;
+        // normalize before anything is checked against, or written to, the indexes
+        let policy = self.uri_policy(deps.storage)?;
+        msg.token_uri = msg
+            .token_uri
+            .as_deref()
+            .map(|token_uri| normalize_uri(token_uri, &policy))
+            .transpose()?;
+        if let Some(image) = msg.extension.get_image_raw() {
+            msg.extension
+                .set_image(Some(normalize_uri(&image, &policy)?));
+        }
+        let extension_uri = msg.extension.get_token_uri();
+        if !extension_uri.is_empty() {
+            msg.extension
+                .set_token_uri(&normalize_uri(&extension_uri, &policy)?);
+        }
 
         // create the token
+        let serial = self.next_serial(deps.storage)?;
         let token = TokenInfo {
             owner: deps.api.addr_validate(&msg.owner)?,
             approvals: vec![],
             token_uri: msg.token_uri.clone(),
+            locked: None,
+            user: None,
+            metadata_delegates: vec![],
+            serial,
             extension: msg.extension.clone(), /*
                                               change_count: 0,
                                               unique_owners: vec![],
@@ -208,14 +373,14 @@ where
                                               */
         };
         if let Some(token_uri) = msg.token_uri.clone() {
-            if let Ok(_x) = self.tokens_uri.load(deps.storage, &token_uri) {
+            if let Ok(_x) = self.tokens_uri.load(deps.storage, &uri_key(&token_uri)) {
                 return Err(ContractError::Claimed {});
             }
         } else {
             return Err(ContractError::TokenMissing {});
         }
         if let Some(image_uri) = msg.extension.get_image_raw() {
-            if let Ok(_x) = self.image_uri.load(deps.storage, &image_uri) {
+            if let Ok(_x) = self.image_uri.load(deps.storage, &uri_key(&image_uri)) {
                 return Err(ContractError::ImageClaimed {});
             }
         } else {
@@ -229,25 +394,29 @@ where
             })?;
         if let Some(token_uri) = msg.token_uri.clone() {
             self.tokens_uri
-                .update(deps.storage, &token_uri, |old| match old {
+                .update(deps.storage, &uri_key(&token_uri), |old| match old {
                     Some(_) => Err(ContractError::Claimed {}),
-                    None => Ok(token_uri.clone()),
+                    None => Ok(msg.token_id.clone()),
                 })?;
         }
         if let Some(image_uri) = msg.extension.get_image_raw() {
             self.image_uri
-                .update(deps.storage, &image_uri, |old| match old {
+                .update(deps.storage, &uri_key(&image_uri), |old| match old {
                     Some(_) => Err(ContractError::ImageClaimed {}),
                     None => Ok(msg.token_id.clone()),
                 })?;
         }
+        self.add_traits(deps.storage, &msg.token_id, &msg.extension.get_attributes())?;
+        self.serials
+            .save(deps.storage, U64Key::from(serial), &msg.token_id)?;
 
         self.increment_tokens(deps.storage)?;
 
         Ok(Response::new()
             .add_attribute("action", "mint")
             .add_attribute("minter", info.sender)
-            .add_attribute("token_id", msg.token_id))
+            .add_attribute("token_id", msg.token_id)
+            .add_attribute("serial", serial.to_string()))
     }
     fn burn(
         &self,
@@ -258,13 +427,23 @@ where
     ) -> Result<Response<C>, ContractError> {
         let token = self.tokens.load(deps.storage, &token_id)?;
         self.check_can_send(deps.as_ref(), &env, &info, &token)?;
+        if self.stakes.may_load(deps.storage, &token_id)?.is_some() {
+            return Err(ContractError::Staked {});
+        }
 
         self.tokens.remove(deps.storage, &token_id)?;
         if let Some(image) = &token.extension.get_image_raw() {
-            self.image_uri.remove(deps.storage, image)?;
+            self.image_uri.remove(deps.storage, &uri_key(image))?;
         }
         if let Some(token_uri) = &token.token_uri {
-            self.tokens_uri.remove(deps.storage, token_uri)?;
+            self.tokens_uri.remove(deps.storage, &uri_key(token_uri))?;
+        }
+        self.change_dynamics.remove(deps.storage, &token_id)?;
+        self.frozen_tokens.remove(deps.storage, &token_id);
+        self.remove_traits(deps.storage, &token_id, &token.extension.get_attributes())?;
+        if token.serial > 0 {
+            self.serials
+                .remove(deps.storage, U64Key::from(token.serial));
         }
 
         self.decrement_tokens(deps.storage)?;
@@ -279,6 +458,115 @@ where
             .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
+
+    /// like send_nft, but the receive message goes out as a sub-message so we get to see how it went
+    #[allow(clippy::too_many_arguments)]
+    pub fn send_nft_tracked(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        contract: String,
+        token_id: String,
+        msg: Binary,
+        reply_on: ReplyOn,
+    ) -> Result<Response<C>, ContractError> {
+        // Transfer token
+        self._transfer_nft(deps.branch(), &env, &info, &contract, &token_id)?;
+        let contract_addr = deps.api.addr_validate(&contract)?;
+
+        let id = self.next_send_id(deps.storage)?;
+        self.pending_send.save(
+            deps.storage,
+            U64Key::from(id),
+            &PendingSend {
+                token_id: token_id.clone(),
+                sender: info.sender.clone(),
+                contract: contract_addr,
+                block_number: env.block.height,
+                reply_on,
+            },
+        )?;
+
+        let send = Cw721ReceiveMsg {
+            sender: info.sender.to_string(),
+            token_id: token_id.clone(),
+            msg,
+        };
+        // we always want the reply, so the outcome gets recorded and the pending send cleared.
+        // the sender's reply_on decides what a failure does
+        let sub_msg = SubMsg {
+            id,
+            msg: send.into_cosmos_msg(contract.clone())?,
+            gas_limit: None,
+            reply_on: ReplyOn::Always,
+        };
+
+        Ok(Response::new()
+            .add_submessage(sub_msg)
+            .add_attribute("action", "send_nft")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", contract)
+            .add_attribute("token_id", token_id)
+            .add_attribute("send_id", id.to_string()))
+    }
+
+    pub fn reply(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        msg: Reply,
+    ) -> Result<Response<C>, ContractError> {
+        let pending = match self
+            .pending_send
+            .may_load(deps.storage, U64Key::from(msg.id))?
+        {
+            Some(pending) => pending,
+            None => return Err(ContractError::UnknownReply { id: msg.id }),
+        };
+        self.pending_send.remove(deps.storage, U64Key::from(msg.id));
+        let mut record = SendRecord {
+            id: msg.id,
+            sender: pending.sender,
+            contract: pending.contract,
+            block_number: pending.block_number,
+            reply_on: pending.reply_on,
+            success: true,
+            data: None,
+            error: None,
+        };
+
+        let response = Response::new()
+            .add_attribute("action", "send_nft_reply")
+            .add_attribute("token_id", pending.token_id.clone())
+            .add_attribute("send_id", msg.id.to_string());
+        let response = match msg.result {
+            ContractResult::Ok(result) => {
+                record.data = result.data;
+                response.add_attribute("result", "success")
+            }
+            ContractResult::Err(error) => {
+                // replying only on success means the sender didn't want failures kept
+                if record.reply_on == ReplyOn::Success
+                    || self.send_error_policy(deps.storage)? == SendErrorPolicy::Revert
+                {
+                    return Err(ContractError::SendFailed { error });
+                }
+                record.success = false;
+                record.error = Some(error.clone());
+                response
+                    .add_attribute("result", "error")
+                    .add_attribute("error", error)
+            }
+        };
+        self.send_history.save(
+            deps.storage,
+            (&pending.token_id, U64Key::from(msg.id)),
+            &record,
+        )?;
+
+        Ok(response)
+    }
 }
 
 // TODO pull this into some sort of trait extension??
@@ -290,7 +578,7 @@ where
     pub fn buy(
         &self,
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
         msg: BuyMsg, //<T>,
     ) -> Result<Response<C>, ContractError> {
@@ -302,18 +590,14 @@ where
         if count >= max_issuance {
             return Err(ContractError::MaxIssued {});
         }
+        self.check_not_frozen(deps.storage, None)?;
         let public_key_str = self.public_key.load(deps.storage)?;
         let public_key = base64::decode(&public_key_str).unwrap();
         Self::check_pubkey(&public_key)?;
 
         let minimum_amount = self.mint_amount.load(deps.storage)?;
-        if let Some(coins) = info.funds.first() {
-            if coins.denom != "uluna" || coins.amount < Uint128::from(minimum_amount) {
-                return Err(ContractError::Funds {});
-            }
-        } else {
-            return Err(ContractError::Funds {});
-        }
+        let refund =
+            Self::take_payment(&deps.querier, &info, "uluna", Uint128::from(minimum_amount))?;
         let hash_message = format!("{}/{}", info.sender, msg.attributes);
         //println!("{}", hash_message);
         let hash = Sha256::digest(hash_message.as_bytes());
@@ -326,21 +610,34 @@ where
 
         if result {
             let mut extension_copy: T = serde_json_wasm::from_str(&msg.attributes)?;
-            let token_uri = extension_copy.get_token_uri();
+            let policy = self.uri_policy(deps.storage)?;
+            let token_uri = normalize_uri(&extension_copy.get_token_uri(), &policy)?;
+            extension_copy.set_token_uri(&token_uri);
+            if let Some(image) = extension_copy.get_image_raw() {
+                extension_copy.set_image(Some(normalize_uri(&image, &policy)?));
+            }
+            let seed = self.mint_seed(deps.storage, &env, &info.sender)?;
             if let Some(token_id) = msg.buy_metadata.perform_mint(&mut extension_copy) {
+                // the names aren't signed, so they get the same checks as a rename
+                self.check_new_name(deps.storage, &token_id)?;
                 extension_copy.set_status("Alive and curious");
                 // create the token
+                let serial = self.next_serial(deps.storage)?;
                 let token = TokenInfo {
                     owner: info.sender.clone(),
                     approvals: vec![],
                     token_uri: Some(token_uri.clone()),
                     extension: extension_copy.clone(),
+                    locked: None,
+                    user: None,
+                    metadata_delegates: vec![],
+                    serial,
                 };
-                if let Ok(_x) = self.tokens_uri.load(deps.storage, &token_uri) {
+                if let Ok(_x) = self.tokens_uri.load(deps.storage, &uri_key(&token_uri)) {
                     return Err(ContractError::Claimed {});
                 }
                 if let Some(image_uri) = extension_copy.get_image_raw() {
-                    if let Ok(_x) = self.image_uri.load(deps.storage, &image_uri) {
+                    if let Ok(_x) = self.image_uri.load(deps.storage, &uri_key(&image_uri)) {
                         return Err(ContractError::ImageClaimed {});
                     }
                 } else {
@@ -353,24 +650,40 @@ where
                         None => Ok(token),
                     })?;
                 self.tokens_uri
-                    .update(deps.storage, &token_uri, |old| match old {
+                    .update(deps.storage, &uri_key(&token_uri), |old| match old {
                         Some(_) => Err(ContractError::Claimed {}),
                         None => Ok(token_id.clone()),
                     })?;
                 // note.. we checked this above
                 let image_uri = extension_copy.get_image_raw().unwrap_or_default();
                 self.image_uri
-                    .update(deps.storage, &image_uri, |old| match old {
+                    .update(deps.storage, &uri_key(&image_uri), |old| match old {
                         Some(_) => Err(ContractError::ImageClaimed {}),
                         None => Ok(token_id.clone()),
                     })?;
+                self.add_traits(deps.storage, &token_id, &extension_copy.get_attributes())?;
+                self.serials
+                    .save(deps.storage, U64Key::from(serial), &token_id)?;
+                if let Some(seed) = &seed {
+                    self.pending_rolls.save(
+                        deps.storage,
+                        U64Key::from(serial),
+                        &Binary::from(seed.clone()),
+                    )?;
+                }
 
                 self.increment_tokens(deps.storage)?;
 
-                Ok(Response::new()
+                let mut response = Response::new()
+                    .add_messages(refund)
                     .add_attribute("action", "mint")
                     .add_attribute("minter", info.sender)
-                    .add_attribute("token_id", token_id))
+                    .add_attribute("token_id", token_id)
+                    .add_attribute("serial", serial.to_string());
+                if let Some(seed) = seed {
+                    response = response.add_attribute("seed", base64::encode(seed));
+                }
+                Ok(response)
             } else {
                 Err(ContractError::BadTokenId {})
             }
@@ -378,6 +691,304 @@ where
             Err(ContractError::BadSignature {})
         }
     }
+    /// the buy's part of the seed for picking traits, if the contract rolls them. different for
+    /// every buy: it mixes the block, the buyer and the number of tokens so far. The traits
+    /// aren't known until the admin's salt is mixed in at reveal
+    fn mint_seed(
+        &self,
+        storage: &dyn Storage,
+        env: &Env,
+        sender: &Addr,
+    ) -> StdResult<Option<Vec<u8>>> {
+        if self.mint_salt_hash.may_load(storage)?.is_none() {
+            return Ok(None);
+        }
+        let seed = Sha256::new()
+            .chain(env.block.chain_id.as_bytes())
+            .chain(env.block.height.to_be_bytes())
+            .chain(env.block.time.nanos().to_be_bytes())
+            .chain(sender.as_bytes())
+            .chain(self.token_count(storage)?.to_be_bytes())
+            .result();
+        Ok(Some(seed.to_vec()))
+    }
+
+    /// picks a label for every weighted trait in the trait map
+    fn pick_traits(&self, storage: &dyn Storage, seed: &[u8]) -> StdResult<Vec<(String, String)>> {
+        let mut picked = vec![];
+        for (trait_type, summaries) in self.nft_contract_trait_map(storage)? {
+            let total: u64 = summaries
+                .iter()
+                .map(|s| u64::from(s.weight.unwrap_or_default()))
+                .sum();
+            if total == 0 {
+                continue;
+            }
+            let roll = Sha256::new()
+                .chain(seed)
+                .chain(trait_type.as_bytes())
+                .result();
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&roll[0..8]);
+            let mut roll = u64::from_be_bytes(bytes) % total;
+            for summary in summaries {
+                let weight = u64::from(summary.weight.unwrap_or_default());
+                if roll < weight {
+                    picked.push((trait_type.clone(), summary.label));
+                    break;
+                }
+                roll -= weight;
+            }
+        }
+        Ok(picked)
+    }
+
+    pub fn set_info_max_limit(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        limit: u32,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        if limit == 0 {
+            return Err(ContractError::InvalidLimits {});
+        }
+        self.info_max_limit.save(deps.storage, &limit)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_info_max_limit")
+            .add_attribute("sender", info.sender)
+            .add_attribute("limit", limit.to_string()))
+    }
+
+    pub fn repair_page(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        start_after: Option<AuditCursor>,
+        limit: Option<u32>,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        let limit = self.page_limit(deps.storage, limit)?;
+        let page = self.audit(deps.storage, start_after, limit)?;
+        let repaired = self.repair(deps.storage, &page.issues)?;
+
+        Ok(Response::new()
+            .set_data(to_binary(&AuditIntegrityResponse {
+                issues: page.issues.clone(),
+                next: page.next,
+            })?)
+            .add_attribute("action", "repair")
+            .add_attribute("sender", info.sender)
+            .add_attribute("repaired", repaired.to_string()))
+    }
+
+    pub fn backfill_indexes(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        if limit == Some(0) {
+            return Err(ContractError::ZeroLimit {});
+        }
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(Bound::exclusive);
+        // one past the page, to tell whether this is the last one
+        let mut tokens = self
+            .tokens
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit + 1)
+            .collect::<StdResult<Vec<_>>>()?;
+        let done = tokens.len() <= limit;
+        tokens.truncate(limit);
+        let mut last_token_id = None;
+        for (k, mut token) in tokens {
+            let token_id = String::from_utf8_lossy(&k).to_string();
+            // their mint order is lost, they're numbered in token_id order after the last serial
+            if token.serial == 0 {
+                token.serial = self.next_serial(deps.storage)?;
+                self.serials
+                    .save(deps.storage, U64Key::from(token.serial), &token_id)?;
+            }
+            // saving over the token writes its index entries
+            self.tokens.save(deps.storage, &token_id, &token)?;
+            let mut missing = vec![];
+            for t in token.extension.get_attributes() {
+                if !self.token_traits.has(
+                    deps.storage,
+                    (t.trait_type.as_str(), t.value.as_str(), token_id.as_str()),
+                ) {
+                    missing.push(t);
+                }
+            }
+            self.add_traits(deps.storage, &token_id, &missing)?;
+            last_token_id = Some(token_id);
+        }
+
+        let mut res = Response::new()
+            .add_attribute("action", "backfill_indexes")
+            .add_attribute("sender", info.sender)
+            .add_attribute("done", done.to_string());
+        if let Some(last_token_id) = last_token_id {
+            res = res.add_attribute("last_token_id", last_token_id);
+        }
+        Ok(res)
+    }
+
+    pub fn set_query_limits(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        default_limit: u32,
+        max_limit: u32,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        if default_limit == 0 || default_limit > max_limit {
+            return Err(ContractError::InvalidLimits {});
+        }
+        self.query_limits.save(
+            deps.storage,
+            &QueryLimits {
+                default_limit,
+                max_limit,
+            },
+        )?;
+        Ok(Response::new()
+            .add_attribute("action", "set_query_limits")
+            .add_attribute("sender", info.sender)
+            .add_attribute("default_limit", default_limit.to_string())
+            .add_attribute("max_limit", max_limit.to_string()))
+    }
+
+    pub fn set_mint_salt(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        salt_hash: Option<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        match salt_hash {
+            Some(salt_hash) => {
+                if salt_hash.len() != 64 || !salt_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(ContractError::InvalidSaltHash {});
+                }
+                self.mint_salt_hash
+                    .save(deps.storage, &salt_hash.to_ascii_lowercase())?
+            }
+            None => self.mint_salt_hash.remove(deps.storage),
+        }
+        Ok(Response::new()
+            .add_attribute("action", "set_mint_salt")
+            .add_attribute("sender", info.sender))
+    }
+
+    /// checks the salt against the commitment, then picks the traits of a page of the buys
+    /// waiting on it
+    pub fn reveal_mint_salt(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        salt: String,
+        limit: Option<u32>,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        if limit == Some(0) {
+            return Err(ContractError::ZeroLimit {});
+        }
+        match self.mint_salt_hash.may_load(deps.storage)? {
+            Some(salt_hash) => {
+                let hash: String = Sha256::digest(salt.as_bytes())
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect();
+                if hash != salt_hash {
+                    return Err(ContractError::SaltMismatch {});
+                }
+                // once it is public, new buys could work their traits out
+                self.mint_salt_hash.remove(deps.storage);
+                self.revealed_salt.save(deps.storage, &salt)?;
+            }
+            None => {
+                if self.revealed_salt.may_load(deps.storage)? != Some(salt.clone()) {
+                    return Err(ContractError::SaltMismatch {});
+                }
+            }
+        }
+        let limit = self.page_limit(deps.storage, limit)?;
+        // one past the page, to tell whether this is the last one
+        let mut rolls = self
+            .pending_rolls
+            .range(deps.storage, None, None, Order::Ascending)
+            .take(limit + 1)
+            .collect::<StdResult<Vec<_>>>()?;
+        let done = rolls.len() <= limit;
+        rolls.truncate(limit);
+        let mut revealed = 0u32;
+        for (serial, seed) in rolls {
+            let serial = U64Key::from(serial);
+            // the serial follows renames, and is gone once the token is burned
+            let token_id = self.serials.may_load(deps.storage, serial.clone())?;
+            self.pending_rolls.remove(deps.storage, serial);
+            let token_id = match token_id {
+                Some(token_id) => token_id,
+                None => continue,
+            };
+            let mut token = self.tokens.load(deps.storage, &token_id)?;
+            let seed = Sha256::new()
+                .chain(seed.as_slice())
+                .chain(salt.as_bytes())
+                .result();
+            let old_traits = token.extension.get_attributes();
+            for (trait_type, value) in self.pick_traits(deps.storage, &seed)? {
+                token.extension.set_trait(&trait_type, &value);
+            }
+            self.remove_traits(deps.storage, &token_id, &old_traits)?;
+            self.add_traits(deps.storage, &token_id, &token.extension.get_attributes())?;
+            self.tokens.save(deps.storage, &token_id, &token)?;
+            revealed += 1;
+        }
+        if done {
+            self.revealed_salt.remove(deps.storage);
+        }
+        Ok(Response::new()
+            .add_attribute("action", "reveal_mint_salt")
+            .add_attribute("sender", info.sender)
+            .add_attribute("revealed", revealed.to_string())
+            .add_attribute("done", done.to_string()))
+    }
+
     pub fn set_public_key(
         &self,
         deps: DepsMut,
@@ -458,97 +1069,852 @@ where
             .add_attribute("change_multiplier", change_multiplier_string))
     }
 
-    pub fn set_image_prefix(
+    pub fn set_change_pricing(
         &self,
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        prefix: String,
+        kind: ChangeKind,
+        pricing: ChangePricing,
     ) -> Result<Response<C>, ContractError> {
         let minter = self.minter.load(deps.storage)?;
 
         if info.sender != minter {
             return Err(ContractError::Unauthorized {});
         }
-        self.image_prefix.save(deps.storage, &prefix)?;
+        match kind {
+            ChangeKind::Rename => self.rename_pricing.save(deps.storage, &pricing)?,
+            ChangeKind::Description => self.description_pricing.save(deps.storage, &pricing)?,
+        }
         Ok(Response::new()
             .add_attribute("action", "approve")
             .add_attribute("sender", info.sender)
-            .add_attribute("image_prefix", prefix))
+            .add_attribute("change_kind", format!("{:?}", kind))
+            .add_attribute("change_pricing", serde_json_wasm::to_string(&pricing)?))
     }
 
-    pub fn set_nft_keybase_verification(
+    /// renames the token and/or resets its status, without fees or the owner's say.
+    /// counted in the token's change history, but doesn't make the owner's next change dearer
+    pub fn moderate_token(
         &self,
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        message: String,
+        token_id: String,
+        name: Option<String>,
+        status: Option<String>,
     ) -> Result<Response<C>, ContractError> {
         let minter = self.minter.load(deps.storage)?;
 
         if info.sender != minter {
             return Err(ContractError::Unauthorized {});
         }
-        self.keybase_message
-            .save(deps.storage, &Some(message.clone()))?;
-        Ok(Response::new()
-            .add_attribute("action", "approve")
+        // an empty name leaves the name as it is
+        if name.as_deref().unwrap_or_default().is_empty() && status.is_none() {
+            return Err(ContractError::NothingToModerate {});
+        }
+        match &name {
+            Some(nam) if !nam.is_empty() => self.check_new_name(deps.storage, nam)?,
+            _ => {}
+        }
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_not_frozen(deps.storage, Some(&token_id))?;
+        let mut change_dynamics = self.change_dynamics_or_new(deps.storage, &token_id, &token)?;
+        change_dynamics.moderation_count += 1;
+        if let Some(status) = &status {
+            token.extension.set_status(status);
+        }
+        self.save_renamed(
+            deps.storage,
+            &token_id,
+            &name,
+            &mut token,
+            &mut change_dynamics,
+        )?;
+
+        let mut res = Response::new()
+            .add_attribute("action", "moderate_token")
             .add_attribute("sender", info.sender)
-            .add_attribute("nft_keybase_verification", message))
+            .add_attribute("token_id", token_id);
+        if let Some(name) = name {
+            res = res.add_attribute("name", name);
+        }
+        if let Some(status) = status {
+            res = res.add_attribute("status", status);
+        }
+        Ok(res)
     }
 
-    pub fn set_nft_trait_map(
+    pub fn add_blocked_names(
         &self,
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        trait_map: Vec<(String, Vec<NftTraitSummary>)>,
+        names: Vec<String>,
     ) -> Result<Response<C>, ContractError> {
         let minter = self.minter.load(deps.storage)?;
 
         if info.sender != minter {
             return Err(ContractError::Unauthorized {});
         }
-        match serde_json_wasm::to_string(&trait_map) {
-            Ok(json) => {
-                self.trait_map.save(deps.storage, &trait_map.clone())?;
-                Ok(Response::new()
-                    .add_attribute("action", "approve")
-                    .add_attribute("sender", info.sender)
-                    .add_attribute("nft_trait_map", json))
-            }
-            Err(e) => Err(ContractError::JsonSerError(e)),
+        for name in &names {
+            self.blocked_names
+                .save(deps.storage, &name.to_lowercase(), &Empty {})?;
         }
+        Ok(Response::new()
+            .add_attribute("action", "add_blocked_names")
+            .add_attribute("sender", info.sender)
+            .add_attribute("count", names.len().to_string()))
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn set_nft_contract_info(
+    pub fn remove_blocked_names(
         &self,
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
-        description: Option<String>,
-        src: Option<String>,
-        banner_src: Option<String>,
-        twitter: Option<String>,
-        github: Option<String>,
-        discord: Option<String>,
-        telegram: Option<String>,
-        listing: Vec<NftListing>,
+        names: Vec<String>,
     ) -> Result<Response<C>, ContractError> {
         let minter = self.minter.load(deps.storage)?;
 
         if info.sender != minter {
             return Err(ContractError::Unauthorized {});
         }
-        let nft_contract_info = crate::state::NftContractInfo {
-            description,
-            src,
-            banner_src,
-            twitter,
-            github,
-            discord,
-            telegram,
+        for name in &names {
+            self.blocked_names
+                .remove(deps.storage, &name.to_lowercase());
+        }
+        Ok(Response::new()
+            .add_attribute("action", "remove_blocked_names")
+            .add_attribute("sender", info.sender)
+            .add_attribute("count", names.len().to_string()))
+    }
+
+    pub fn set_content_policy(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        policy: ContentPolicy,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        self.content_policy.save(deps.storage, &policy)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_content_policy")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn set_uri_policy(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        policy: UriPolicy,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        self.uri_policy.save(deps.storage, &policy)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_uri_policy")
+            .add_attribute("sender", info.sender)
+            .add_attribute("max_length", policy.max_length.to_string()))
+    }
+
+    pub fn set_uri_prefix(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        field: UriField,
+        prefix: String,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        self.check_not_frozen(deps.storage, None)?;
+        self.uri_prefixes
+            .save(deps.storage, field.as_str(), &prefix)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_uri_prefix")
+            .add_attribute("sender", info.sender)
+            .add_attribute("field", field.as_str())
+            .add_attribute("prefix", prefix))
+    }
+
+    pub fn set_soulbound(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        soulbound: bool,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        self.soulbound.save(deps.storage, &soulbound)?;
+        Ok(Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", info.sender)
+            .add_attribute("soulbound", soulbound.to_string()))
+    }
+
+    pub fn lock_token(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        let minter = self.minter.load(deps.storage)?;
+        if info.sender != token.owner && info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        // only the minter can unlock early, so an owner's lock has to end by itself
+        let expires = match expires {
+            None | Some(Expiration::Never {}) if info.sender != minter => {
+                return Err(ContractError::LockNeedsExpiry {})
+            }
+            expires => expires.unwrap_or_default(),
+        };
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::Expired {});
+        }
+        // the owner can extend an active lock, but only the minter can shorten or replace one
+        if info.sender != minter && token.is_locked(&env.block) {
+            if let Some(locked) = &token.locked {
+                match expires.partial_cmp(locked) {
+                    Some(Ordering::Less) => return Err(ContractError::Locked {}),
+                    Some(_) => {}
+                    None => return Err(ContractError::LockExpiryMismatch {}),
+                }
+            }
+        }
+        token.locked = Some(expires);
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "lock_token")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("expires", expires.to_string()))
+    }
+
+    pub fn unlock_token(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        let minter = self.minter.load(deps.storage)?;
+        if info.sender != minter {
+            if info.sender != token.owner {
+                return Err(ContractError::Unauthorized {});
+            }
+            if token.is_locked(&env.block) {
+                return Err(ContractError::Locked {});
+            }
+        }
+        token.locked = None;
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "unlock_token")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn set_user(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        user: Option<String>,
+        expires: Option<Expiration>,
+    ) -> Result<Response<C>, ContractError> {
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_can_approve(deps.as_ref(), &env, &info, &token)?;
+
+        let response = Response::new()
+            .add_attribute("action", "set_user")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id.clone());
+        let response = match user {
+            Some(user) => {
+                // reject expired data as invalid
+                let expires = expires.unwrap_or_default();
+                if expires.is_expired(&env.block) {
+                    return Err(ContractError::Expired {});
+                }
+                token.user = Some(TokenUser {
+                    user: deps.api.addr_validate(&user)?,
+                    expires,
+                });
+                response
+                    .add_attribute("user", user)
+                    .add_attribute("expires", expires.to_string())
+            }
+            None => {
+                token.user = None;
+                response
+            }
+        };
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(response)
+    }
+
+    pub fn update_metadata_delegate(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        delegate: String,
+        token_id: Option<String>,
+        // if add == false, remove
+        add: bool,
+    ) -> Result<Response<C>, ContractError> {
+        let delegate_addr = deps.api.addr_validate(&delegate)?;
+        let response = Response::new()
+            .add_attribute(
+                "action",
+                if add {
+                    "add_metadata_delegate"
+                } else {
+                    "remove_metadata_delegate"
+                },
+            )
+            .add_attribute("sender", info.sender.clone())
+            .add_attribute("delegate", delegate);
+
+        match token_id {
+            Some(token_id) => {
+                let mut token = self.tokens.load(deps.storage, &token_id)?;
+                if token.owner != info.sender {
+                    return Err(ContractError::Unauthorized {});
+                }
+                token.metadata_delegates.retain(|d| d != &delegate_addr);
+                if add {
+                    token.metadata_delegates.push(delegate_addr);
+                }
+                self.tokens.save(deps.storage, &token_id, &token)?;
+                Ok(response.add_attribute("token_id", token_id))
+            }
+            None => {
+                if add {
+                    self.owner_metadata_delegates.save(
+                        deps.storage,
+                        (&info.sender, &delegate_addr),
+                        &Empty {},
+                    )?;
+                } else {
+                    self.owner_metadata_delegates
+                        .remove(deps.storage, (&info.sender, &delegate_addr));
+                }
+                Ok(response)
+            }
+        }
+    }
+
+    pub fn stake(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        duration: u64,
+    ) -> Result<Response<C>, ContractError> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        if info.sender != token.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        let now = env.block.time.seconds();
+        let unlocks_at = now
+            .checked_add(duration)
+            .ok_or(ContractError::StakeTooLong {})?;
+        let stake = Stake {
+            owner: token.owner,
+            token_id: token_id.clone(),
+            staked_at: now,
+            unlocks_at,
+        };
+        self.stakes
+            .update(deps.storage, &token_id, |old| match old {
+                Some(_) => Err(ContractError::Staked {}),
+                None => Ok(stake),
+            })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "stake")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("unlocks_at", unlocks_at.to_string()))
+    }
+
+    pub fn unstake(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let stake = self
+            .stakes
+            .may_load(deps.storage, &token_id)?
+            .ok_or(ContractError::NotStaked {})?;
+        if info.sender != stake.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        let now = env.block.time.seconds();
+        if now < stake.unlocks_at {
+            return Err(ContractError::StakeLocked {
+                unlocks_at: stake.unlocks_at,
+            });
+        }
+        self.stakes.remove(deps.storage, &token_id);
+
+        let staked_time = now - stake.staked_at;
+        self.token_staked_time
+            .update(deps.storage, &token_id, |old| -> StdResult<u64> {
+                Ok(old.unwrap_or_default() + staked_time)
+            })?;
+        self.owner_staked_time
+            .update(deps.storage, &stake.owner, |old| -> StdResult<u64> {
+                Ok(old.unwrap_or_default() + staked_time)
+            })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "unstake")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id)
+            .add_attribute("staked_time", staked_time.to_string()))
+    }
+
+    pub fn update_token_metadata(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: String,
+        mut patch: MetadataPatch,
+    ) -> Result<Response<C>, ContractError> {
+        if patch.fields().is_empty() {
+            return Err(ContractError::EmptyPatch {});
+        }
+        if let Some(image) = &patch.image {
+            patch.image = Some(normalize_uri(image, &self.uri_policy(deps.storage)?)?);
+        }
+        if let Some(description) = &patch.description {
+            check_description(&self.content_policy(deps.storage)?, description)?;
+        }
+        let mut token = self.tokens.load(deps.storage, &token_id)?;
+        self.check_not_frozen(deps.storage, Some(&token_id))?;
+        let is_admin = info.sender == self.minter.load(deps.storage)?;
+        let access = self.field_access(deps.storage)?;
+        let mut charged = false;
+        for field in patch.fields() {
+            match access.get(field) {
+                FieldAccess::Immutable => {
+                    return Err(ContractError::FieldImmutable {
+                        field: format!("{:?}", field),
+                    })
+                }
+                FieldAccess::Admin if !is_admin => return Err(ContractError::Unauthorized {}),
+                FieldAccess::Owner if !is_admin => {
+                    self.check_can_edit_metadata(deps.as_ref(), &info, &token)?;
+                    // owners pay for descriptions the same as through SetTokenNameDescription
+                    charged |= field == MetadataField::Description;
+                }
+                _ => {}
+            }
+        }
+
+        let old_image = token.extension.get_image_raw().map(|image| uri_key(&image));
+        let old_attributes = token.extension.get_attributes();
+        if let Some(attributes) = &patch.attributes {
+            // the name trait follows the token id, it changes with a rename
+            let name_of = |traits: &[Trait]| {
+                traits
+                    .iter()
+                    .find(|t| t.trait_type == "name")
+                    .map(|t| t.value.clone())
+            };
+            if name_of(attributes) != name_of(&old_attributes) {
+                return Err(ContractError::ReservedTrait {
+                    trait_type: "name".to_string(),
+                });
+            }
+        }
+        let refund = if charged {
+            let mut change_dynamics =
+                self.change_dynamics_or_new(deps.storage, &token_id, &token)?;
+            let cost = self
+                .change_pricing(deps.storage, &ChangeKind::Description)?
+                .price(change_dynamics.change_count)?;
+            let refund = Self::take_payment(&deps.querier, &info, "uusd", cost)?;
+            change_dynamics.change_count += 1;
+            self.change_dynamics
+                .save(deps.storage, &token_id, &change_dynamics)?;
+            refund
+        } else {
+            nonpayable(&info)?;
+            None
+        };
+        token.extension.apply_patch(&patch);
+
+        let new_image = token.extension.get_image_raw().map(|image| uri_key(&image));
+        if new_image != old_image {
+            if let Some(image) = &new_image {
+                self.image_uri
+                    .update(deps.storage, image, |old| match old {
+                        Some(_) => Err(ContractError::ImageClaimed {}),
+                        None => Ok(token_id.clone()),
+                    })?;
+            }
+            if let Some(image) = &old_image {
+                self.image_uri.remove(deps.storage, image)?;
+            }
+        }
+        if patch.attributes.is_some() {
+            self.remove_traits(deps.storage, &token_id, &old_attributes)?;
+            self.add_traits(deps.storage, &token_id, &token.extension.get_attributes())?;
+        }
+        self.tokens.save(deps.storage, &token_id, &token)?;
+
+        Ok(Response::new()
+            .add_messages(refund)
+            .add_attribute("action", "update_token_metadata")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn set_provenance(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        provenance_hash: String,
+        reveal_height: Option<u64>,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        // mint_sequence is newer than some collections, their earlier sales only show in the count
+        if self
+            .mint_sequence
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            > 0
+            || self.token_count(deps.storage)? > 0
+        {
+            return Err(ContractError::ProvenanceLocked {});
+        }
+        let provenance = Provenance {
+            provenance_hash: Self::check_provenance_hash(&provenance_hash)?,
+            collection_size: self.max_issuance.load(deps.storage)?,
+            starting_index: None,
+            starting_index_block: None,
+            reveal_height,
+        };
+        self.provenance.save(deps.storage, &provenance)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_provenance")
+            .add_attribute("sender", info.sender)
+            .add_attribute("provenance_hash", provenance.provenance_hash))
+    }
+
+    /// picks the starting index in two calls, once the collection has sold out or the reveal
+    /// height has passed: the first records the block, and a call in any later block takes the
+    /// index from a hash of both blocks and the provenance
+    pub fn finalize_starting_index(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+    ) -> Result<Response<C>, ContractError> {
+        let mut provenance = self
+            .provenance
+            .may_load(deps.storage)?
+            .ok_or(ContractError::ProvenanceMissing {})?;
+        if provenance.starting_index.is_some() {
+            return Err(ContractError::StartingIndexSet {});
+        }
+        let requested_at = match provenance.starting_index_block {
+            Some(requested_at) => requested_at,
+            None => {
+                // the same test mint uses, so burns are taken into account
+                let sold_out =
+                    self.token_count(deps.storage)? >= self.max_issuance.load(deps.storage)?;
+                let revealed =
+                    matches!(provenance.reveal_height, Some(height) if env.block.height >= height);
+                if !sold_out && !revealed {
+                    return Err(ContractError::StartingIndexNotDue {});
+                }
+                provenance.starting_index_block = Some(env.block.height);
+                self.provenance.save(deps.storage, &provenance)?;
+                return Ok(Response::new()
+                    .add_attribute("action", "finalize_starting_index")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("starting_index_block", env.block.height.to_string()));
+            }
+        };
+        if env.block.height <= requested_at {
+            return Err(ContractError::StartingIndexTooSoon {});
+        }
+        let seed = Sha256::new()
+            .chain(env.block.chain_id.as_bytes())
+            .chain(requested_at.to_be_bytes())
+            .chain(env.block.height.to_be_bytes())
+            .chain(env.block.time.nanos().to_be_bytes())
+            .chain(provenance.provenance_hash.as_bytes())
+            .result();
+        let mut first = [0u8; 8];
+        first.copy_from_slice(&seed[..8]);
+        let starting_index = u64::from_be_bytes(first) % provenance.collection_size.max(1);
+        provenance.starting_index = Some(starting_index);
+        self.provenance.save(deps.storage, &provenance)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "finalize_starting_index")
+            .add_attribute("sender", info.sender)
+            .add_attribute("starting_index", starting_index.to_string()))
+    }
+
+    /// freezes the collection's metadata on the first call, and hashes the next `limit`
+    /// tokens into the metadata hash on each call until all are done
+    pub fn freeze_metadata(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        limit: Option<u32>,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        let mut freeze = match self.metadata_freeze.may_load(deps.storage)? {
+            Some(freeze) if freeze.done => return Err(ContractError::Frozen {}),
+            Some(freeze) => freeze,
+            None if self
+                .pending_rolls
+                .range(deps.storage, None, None, Order::Ascending)
+                .next()
+                .is_some() =>
+            {
+                return Err(ContractError::RollPending {})
+            }
+            None => MetadataFreeze {
+                frozen_at: env.block.height,
+                hashed_up_to: None,
+                tokens_hashed: 0,
+                hash: Binary::from(Sha256::digest(b"").to_vec()),
+                done: false,
+            },
+        };
+        if limit == Some(0) {
+            return Err(ContractError::ZeroLimit {});
+        }
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = freeze.hashed_up_to.clone().map(Bound::exclusive);
+        // one past the page, to tell whether this is the last one
+        let mut tokens = self
+            .tokens
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit + 1)
+            .collect::<StdResult<Vec<_>>>()?;
+        freeze.done = tokens.len() <= limit;
+        tokens.truncate(limit);
+        for (k, token) in &tokens {
+            let hash = Sha256::new()
+                .chain(freeze.hash.as_slice())
+                .chain(k)
+                .chain(token.token_uri.clone().unwrap_or_default().as_bytes())
+                .chain(to_vec(&token.extension)?)
+                .result();
+            freeze.hash = Binary::from(hash.to_vec());
+            freeze.hashed_up_to = Some(String::from_utf8_lossy(k).to_string());
+            freeze.tokens_hashed += 1;
+        }
+        self.metadata_freeze.save(deps.storage, &freeze)?;
+
+        let mut res = Response::new()
+            .add_attribute("action", "freeze_metadata")
+            .add_attribute("sender", info.sender)
+            .add_attribute("tokens_hashed", freeze.tokens_hashed.to_string());
+        if freeze.done {
+            res = res.add_attribute("metadata_hash", freeze.hash.to_base64());
+        }
+        Ok(res)
+    }
+
+    /// stops any further metadata changes to one token
+    pub fn freeze_token(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        if self
+            .pending_rolls
+            .may_load(deps.storage, U64Key::from(token.serial))?
+            .is_some()
+        {
+            return Err(ContractError::RollPending {});
+        }
+        self.frozen_tokens
+            .save(deps.storage, &token_id, &Empty {})?;
+        Ok(Response::new()
+            .add_attribute("action", "freeze_token")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    pub fn set_metadata_field_access(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        access: MetadataFieldAccess,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        self.field_access.save(deps.storage, &access)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_metadata_field_access")
+            .add_attribute("sender", info.sender))
+    }
+
+    pub fn set_send_error_policy(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        policy: SendErrorPolicy,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        self.send_error_policy.save(deps.storage, &policy)?;
+        Ok(Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", info.sender)
+            .add_attribute("send_error_policy", format!("{:?}", policy)))
+    }
+
+    pub fn set_nft_keybase_verification(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        message: String,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        self.keybase_message
+            .save(deps.storage, &Some(message.clone()))?;
+        Ok(Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", info.sender)
+            .add_attribute("nft_keybase_verification", message))
+    }
+
+    pub fn set_nft_trait_map(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        mut trait_map: Vec<(String, Vec<NftTraitSummary>)>,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        // weighted traits get their rarity from the weights
+        for (_, summaries) in trait_map.iter_mut() {
+            let total: u64 = summaries
+                .iter()
+                .map(|s| u64::from(s.weight.unwrap_or_default()))
+                .sum();
+            if summaries.iter().any(|s| s.weight.is_some()) && total > 0 {
+                for summary in summaries.iter_mut() {
+                    summary.value = Decimal::from_ratio(summary.weight.unwrap_or_default(), total);
+                }
+            }
+        }
+        match serde_json_wasm::to_string(&trait_map) {
+            Ok(json) => {
+                self.trait_map.save(deps.storage, &trait_map.clone())?;
+                Ok(Response::new()
+                    .add_attribute("action", "approve")
+                    .add_attribute("sender", info.sender)
+                    .add_attribute("nft_trait_map", json))
+            }
+            Err(e) => Err(ContractError::JsonSerError(e)),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_nft_contract_info(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        description: Option<String>,
+        src: Option<String>,
+        banner_src: Option<String>,
+        twitter: Option<String>,
+        github: Option<String>,
+        discord: Option<String>,
+        telegram: Option<String>,
+        listing: Vec<NftListing>,
+    ) -> Result<Response<C>, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        let nft_contract_info = crate::state::NftContractInfo {
+            description,
+            src,
+            banner_src,
+            twitter,
+            github,
+            discord,
+            telegram,
             listing,
         };
         match serde_json_wasm::to_string(&nft_contract_info) {
@@ -570,47 +1936,306 @@ where
         env: Env,
         info: MessageInfo,
         denom: String,
+        amount: Option<Uint128>,
+        recipient: Option<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let recipient = self.sweep_recipient(deps.as_ref(), &info, recipient)?;
+
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, denom.clone())?
+            .amount;
+        if balance.is_zero() {
+            return Err(ContractError::NoFunds {});
+        }
+        // the tax is paid by the contract on top of what is sent
+        let available = balance - compute_tax(&deps.querier, balance, &denom)?;
+        let amount = match amount {
+            Some(amount) => {
+                if amount.is_zero() {
+                    return Err(ContractError::NoFunds {});
+                }
+                let tax = tax_on(&deps.querier, amount, &denom)?;
+                // an amount so large the tax overflows it can't be in the balance either
+                if amount
+                    .checked_add(tax)
+                    .map_or(true, |total| total > balance)
+                {
+                    return Err(ContractError::BalanceTooLow { available });
+                }
+                amount
+            }
+            None => available,
+        };
+        if amount.is_zero() {
+            return Err(ContractError::FundsTooSmall {});
+        }
+
+        let msg = self.withdraw(deps, &env, &info.sender, &recipient, denom.clone(), amount)?;
+        Ok(Response::new()
+            .add_attribute("sweep", denom)
+            .add_attribute("recipient", recipient)
+            .add_attribute("amount", amount)
+            .add_message(msg))
+    }
+
+    pub fn sweep_all(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        recipient: Option<String>,
+    ) -> Result<Response<C>, ContractError> {
+        let recipient = self.sweep_recipient(deps.as_ref(), &info, recipient)?;
+
+        let balances = deps.querier.query_all_balances(&env.contract.address)?;
+        let mut messages: Vec<BankMsg> = vec![];
+        for balance in balances {
+            let amount =
+                balance.amount - compute_tax(&deps.querier, balance.amount, &balance.denom)?;
+            // dust that can't cover its own tax stays behind
+            if amount.is_zero() {
+                continue;
+            }
+            messages.push(self.withdraw(
+                deps.branch(),
+                &env,
+                &info.sender,
+                &recipient,
+                balance.denom,
+                amount,
+            )?);
+        }
+        if messages.is_empty() {
+            return Err(ContractError::NoFunds {});
+        }
+
+        Ok(Response::new()
+            .add_attribute("sweep", "all")
+            .add_attribute("recipient", recipient)
+            .add_messages(messages))
+    }
+
+    /// only the minter can sweep, and only while there are no payees to share with
+    fn sweep_recipient(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+        recipient: Option<String>,
+    ) -> Result<Addr, ContractError> {
+        let minter = self.minter.load(deps.storage)?;
+        if info.sender != minter {
+            return Err(ContractError::Unauthorized {});
+        }
+        if !self.payees(deps.storage)?.is_empty() {
+            return Err(ContractError::PayeesSet {});
+        }
+        match recipient {
+            Some(recipient) => Ok(deps.api.addr_validate(&recipient)?),
+            None => Ok(info.sender.clone()),
+        }
+    }
+
+    /// records a payout in the withdrawal log, and returns the message sending it
+    fn withdraw(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        sender: &Addr,
+        recipient: &Addr,
+        denom: String,
+        amount: Uint128,
+    ) -> Result<BankMsg, ContractError> {
+        let tax = tax_on(&deps.querier, amount, &denom)?;
+        let id = self.next_withdrawal_id(deps.storage)?;
+        let coin = Coin { denom, amount };
+        self.withdrawals.save(
+            deps.storage,
+            U64Key::from(id),
+            &Withdrawal {
+                id,
+                sender: sender.clone(),
+                recipient: recipient.clone(),
+                amount: coin.clone(),
+                tax,
+                block_number: env.block.height,
+            },
+        )?;
+        Ok(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin],
+        })
+    }
+    pub fn set_payees(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        payees: Vec<PayeeMsg>,
     ) -> Result<Response<C>, ContractError> {
         let minter = self.minter.load(deps.storage)?;
 
         if info.sender != minter {
             return Err(ContractError::Unauthorized {});
         }
+        let payees = payees
+            .into_iter()
+            .map(|payee| {
+                Ok(Payee {
+                    address: deps.api.addr_validate(&payee.address)?,
+                    share: payee.share,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        if !payees.is_empty()
+            && payees
+                .iter()
+                .fold(Decimal::zero(), |total, payee| total + payee.share)
+                != Decimal::one()
+        {
+            return Err(ContractError::InvalidShares {});
+        }
+        for (i, payee) in payees.iter().enumerate() {
+            if payees[..i].iter().any(|p| p.address == payee.address) {
+                return Err(ContractError::DuplicatePayee {
+                    address: payee.address.to_string(),
+                });
+            }
+        }
 
-        let amount = query_balance(&deps.querier, env.contract.address, denom.clone())?;
-        if amount.is_zero() {
+        // settle up under the old shares, so the change only applies to revenue from here on
+        let old_payees = self.payees(deps.storage)?;
+        let mut messages = vec![];
+        if !old_payees.is_empty() {
+            for balance in deps.querier.query_all_balances(&env.contract.address)? {
+                messages.extend(self.pay_out(
+                    deps.branch(),
+                    &env,
+                    &info.sender,
+                    &old_payees,
+                    &balance.denom,
+                    balance.amount,
+                )?);
+            }
+        }
+        let mut former_payees = self
+            .former_payees
+            .may_load(deps.storage)?
+            .unwrap_or_default();
+        former_payees.retain(|address| !payees.iter().any(|p| &p.address == address));
+        for payee in old_payees {
+            if !payees.iter().any(|p| p.address == payee.address) {
+                former_payees.push(payee.address);
+            }
+        }
+        self.former_payees.save(deps.storage, &former_payees)?;
+        self.payees.save(deps.storage, &payees)?;
+
+        Ok(Response::new()
+            .add_messages(messages)
+            .add_attribute("action", "approve")
+            .add_attribute("sender", info.sender)
+            .add_attribute("payees", payees.len().to_string()))
+    }
+
+    pub fn distribute(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        denom: String,
+    ) -> Result<Response<C>, ContractError> {
+        let payees = self.payees(deps.storage)?;
+        if payees.is_empty() {
+            return Err(ContractError::NoPayees {});
+        }
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, denom.clone())?
+            .amount;
+        if balance.is_zero() {
             return Err(ContractError::NoFunds {});
         }
-        let tax_amount =
-            Self::compute_tax(&deps.querier, amount, denom.clone())? + Uint128::new(1u128);
-        if tax_amount > amount {
+
+        let messages = self.pay_out(deps, &env, &info.sender, &payees, &denom, balance)?;
+        if messages.is_empty() {
             return Err(ContractError::FundsTooSmall {});
         }
+
         Ok(Response::new()
-            .add_attribute("sweep", denom.clone())
-            .add_message(CosmosMsg::Bank(BankMsg::Send {
-                to_address: info.sender.to_string(),
-                amount: vec![Coin {
-                    denom,
-                    amount: amount - tax_amount,
-                }],
-            })))
-    }
-    fn compute_tax(querier: &QuerierWrapper, amount: Uint128, denom: String) -> StdResult<Uint128> {
-        if denom == "uluna" {
-            return Ok(Uint128::zero());
-        }
-
-        let terra_querier = TerraQuerier::new(querier);
-        let tax_rate: Decimal = (terra_querier.query_tax_rate()?).rate;
-        let tax_cap: Uint128 = (terra_querier.query_tax_cap(denom)?).cap;
-        Ok(std::cmp::min(
-            amount.checked_sub(amount.multiply_ratio(
-                DECIMAL_FRACTION,
-                DECIMAL_FRACTION * tax_rate + DECIMAL_FRACTION,
-            ))?,
-            tax_cap,
-        ))
+            .add_messages(messages)
+            .add_attribute("action", "distribute")
+            .add_attribute("sender", info.sender)
+            .add_attribute("denom", denom)
+            .add_attribute("amount", balance))
+    }
+
+    /// splits balance between the payees, by share
+    fn pay_out(
+        &self,
+        mut deps: DepsMut,
+        env: &Env,
+        sender: &Addr,
+        payees: &[Payee],
+        denom: &str,
+        balance: Uint128,
+    ) -> Result<Vec<BankMsg>, ContractError> {
+        let mut messages: Vec<BankMsg> = vec![];
+        for payee in payees {
+            let share = balance * payee.share;
+            if share.is_zero() {
+                continue;
+            }
+            // the tax on the transfer comes out of the payee's share
+            let amount = share - compute_tax(&deps.querier, share, denom)?;
+            if amount.is_zero() {
+                continue;
+            }
+            self.payee_received.update(
+                deps.storage,
+                (&payee.address, denom),
+                |old| -> StdResult<Uint128> { Ok(old.unwrap_or_default() + amount) },
+            )?;
+            messages.push(self.withdraw(
+                deps.branch(),
+                env,
+                sender,
+                &payee.address,
+                denom.to_string(),
+                amount,
+            )?);
+        }
+        Ok(messages)
+    }
+
+    /// makes sure at least `price` of `denom` (and nothing else) was sent,
+    /// and returns the refund of whatever was sent over the price
+    fn take_payment(
+        querier: &QuerierWrapper,
+        info: &MessageInfo,
+        denom: &str,
+        price: Uint128,
+    ) -> Result<Option<BankMsg>, ContractError> {
+        let paid = may_pay(info, denom).map_err(|_| ContractError::Funds {})?;
+        if paid < price {
+            return Err(ContractError::Funds {});
+        }
+        let excess = paid - price;
+        if excess.is_zero() {
+            return Ok(None);
+        }
+        // the tax on the refund comes out of the refund
+        let refund = excess - compute_tax(querier, excess, denom)?;
+        if refund.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: denom.to_string(),
+                amount: refund,
+            }],
+        }))
     }
 }
 
@@ -761,9 +2386,12 @@ where
         let old_owner = token.owner.clone();
         // ensure we have permissions
         self.check_can_send(deps.as_ref(), env, info, &token)?;
+        self.check_not_locked(deps.as_ref(), env, token_id, &token)?;
         // set owner and remove existing approvals
         token.owner = deps.api.addr_validate(recipient)?;
         token.approvals = vec![];
+        token.user = None;
+        token.metadata_delegates = vec![];
         self.tokens.save(deps.storage, token_id, &token)?;
         let mut change_dynamics = match self.change_dynamics.load(deps.storage, token_id) {
             Ok(c) => c,
@@ -777,6 +2405,7 @@ where
                     transfer_count: 0,
                     block_number: 0,
                     price_ceiling: Default::default(),
+                    moderation_count: 0,
                 },
                 _ => return Err(e.into()),
             },
@@ -801,8 +2430,16 @@ where
         status: &str,
     ) -> Result<TokenInfo<T>, ContractError> {
         let mut token = self.tokens.load(deps.storage, token_id)?;
-        // ensure we have permissions
-        self.check_can_send(deps.as_ref(), env, info, &token)?;
+        // ensure we have permissions. the user of the token can also set its status
+        let is_user = token
+            .active_user(&env.block)
+            .map(|user| user.user == info.sender)
+            .unwrap_or(false);
+        if !is_user {
+            self.check_can_edit_metadata(deps.as_ref(), info, &token)?;
+        }
+        self.check_not_frozen(deps.storage, Some(token_id))?;
+        check_status(&self.content_policy(deps.storage)?, status)?;
         // set owner and remove existing approvals
         //  token.owner = deps.api.addr_validate(recipient)?;
         token.extension.set_status(status);
@@ -815,97 +2452,104 @@ where
     pub fn _set_name_description(
         &self,
         deps: DepsMut,
-        env: &Env,
+        _env: &Env,
         info: &MessageInfo,
         token_id: &str,
         name: &Option<String>,
         description: &Option<String>,
-    ) -> Result<TokenInfo<T>, ContractError> {
+    ) -> Result<(TokenInfo<T>, Option<BankMsg>), ContractError> {
         let mut token = self.tokens.load(deps.storage, token_id)?;
-        let mut old_exists = false;
         // ensure we have permissions
-        self.check_can_send(deps.as_ref(), env, info, &token)?;
-        let mut change_dynamics = match self.change_dynamics.load(deps.storage, token_id) {
-            Ok(c) => {
-                old_exists = true;
-                c
-            }
+        self.check_can_edit_metadata(deps.as_ref(), info, &token)?;
+        self.check_not_frozen(deps.storage, Some(token_id))?;
+        if let Some(nam) = name {
+            self.check_new_name(deps.storage, nam)?;
+        }
+        if let Some(desc) = description {
+            check_description(&self.content_policy(deps.storage)?, desc)?;
+        }
+        let mut change_dynamics = self.change_dynamics_or_new(deps.storage, token_id, &token)?;
 
-            Err(e) => match e {
-                StdError::NotFound { .. } => ChangeDynamics {
-                    owner: token.owner.clone(),
-                    token_id: token_id.to_string(),
-                    change_count: 0,
-                    unique_owners: vec![token.owner.clone()],
-                    transfer_count: 0,
-                    block_number: 0,
-                    price_ceiling: Default::default(),
-                },
-                _ => return Err(e.into()),
-            },
+        let kind = match name {
+            Some(nam) if !nam.is_empty() => ChangeKind::Rename,
+            _ => ChangeKind::Description,
         };
-
-        let change_cost = self.change_amount(deps.storage)?;
-        let change_multiplier = self.change_multiplier(deps.storage)?;
-
-        let change_count = change_dynamics.change_count;
-        let cost = change_cost * (change_count / change_multiplier);
-        if cost > 0 {
-            if let Some(coins) = info.funds.first() {
-                if coins.denom != "uusd" || coins.amount < Uint128::from(cost) {
-                    return Err(ContractError::Funds {});
-                }
-            } else {
-                return Err(ContractError::Funds {});
-            }
-        }
+        let cost = self
+            .change_pricing(deps.storage, &kind)?
+            .price(change_dynamics.change_count)?;
+        let refund = Self::take_payment(&deps.querier, info, "uusd", cost)?;
         change_dynamics.change_count += 1;
 
-        // set owner and remove existing approvals
         if let Some(desc) = description {
             token.extension.set_description(Some(desc.clone()));
         }
-        if let Some(nam) = name {
-            if nam.is_empty() {
-                self.tokens.save(deps.storage, token_id, &token)?;
-                self.change_dynamics
-                    .save(deps.storage, token_id, &change_dynamics)?;
-            } else {
-                match self.tokens.load(deps.storage, nam) {
-                    Ok(_) => return Err(ContractError::Claimed {}),
-                    Err(_) => {
-                        token.extension.set_name(Some(nam.clone()));
-                        self.tokens.save(deps.storage, nam, &token)?;
-                        self.tokens.remove(deps.storage, token_id)?;
-                        self.tokens_uri.save(
-                            deps.storage,
-                            &token.extension.get_token_uri(),
-                            nam,
-                        )?;
-
-                        //  self.tokens_uri
-                        //     .remove(deps.storage, &token.extension.get_token_uri())?;
-                        if let Some(img) = token.extension.get_image_raw() {
-                            self.image_uri.save(deps.storage, &img, nam)?;
-                            //   self.image_uri.remove(deps.storage, &img)?;
-                        }
-                        if old_exists {
-                            self.change_dynamics.remove(deps.storage, token_id)?;
-                        }
-                        change_dynamics.token_id = nam.to_string();
-                        self.change_dynamics
-                            .save(deps.storage, nam, &change_dynamics)?;
-                    }
+        self.save_renamed(
+            deps.storage,
+            token_id,
+            name,
+            &mut token,
+            &mut change_dynamics,
+        )?;
+
+        Ok((token, refund))
+    }
+
+    /// the token's change history, or a fresh one if nothing has happened to it yet
+    fn change_dynamics_or_new(
+        &self,
+        storage: &dyn Storage,
+        token_id: &str,
+        token: &TokenInfo<T>,
+    ) -> StdResult<ChangeDynamics> {
+        Ok(self
+            .change_dynamics
+            .may_load(storage, token_id)?
+            .unwrap_or_else(|| ChangeDynamics {
+                owner: token.owner.clone(),
+                token_id: token_id.to_string(),
+                change_count: 0,
+                unique_owners: vec![token.owner.clone()],
+                transfer_count: 0,
+                block_number: 0,
+                price_ceiling: Default::default(),
+                moderation_count: 0,
+            }))
+    }
+
+    /// saves the token and its change history, under the new name when there is one,
+    /// moving its indexes and records along
+    fn save_renamed(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        name: &Option<String>,
+        token: &mut TokenInfo<T>,
+        change_dynamics: &mut ChangeDynamics,
+    ) -> Result<(), ContractError> {
+        match name {
+            Some(nam) if !nam.is_empty() => {
+                if self.tokens.may_load(storage, nam)?.is_some() {
+                    return Err(ContractError::Claimed {});
                 }
+                self.remove_traits(storage, token_id, &token.extension.get_attributes())?;
+                token.extension.set_name(Some(nam.clone()));
+                self.add_traits(storage, nam, &token.extension.get_attributes())?;
+                self.tokens.save(storage, nam, token)?;
+                self.tokens.remove(storage, token_id)?;
+                self.index_token(storage, nam, token)?;
+                self.change_dynamics.remove(storage, token_id)?;
+                self.move_token_records(storage, token_id, nam)?;
+                change_dynamics.token_id = nam.to_string();
+                self.change_dynamics.save(storage, nam, change_dynamics)?;
+            }
+            _ => {
+                // just a description change.. no need to mess with the indexes
+                self.tokens.save(storage, token_id, token)?;
+                self.change_dynamics
+                    .save(storage, token_id, change_dynamics)?;
             }
-        } else {
-            // just a description change.. no need to mess with the indexes
-            self.tokens.save(deps.storage, token_id, &token)?;
-            self.change_dynamics
-                .save(deps.storage, token_id, &change_dynamics)?;
         }
-
-        Ok(token)
+        Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -926,14 +2570,11 @@ where
 
         // update the approval list (remove any for the same spender before adding)
         let spender_addr = deps.api.addr_validate(spender)?;
-        token.approvals = token
-            .approvals
-            .into_iter()
-            .filter(|apr| apr.spender != spender_addr)
-            .collect();
+        token.approvals.retain(|apr| apr.spender != spender_addr);
 
         // only difference between approve and revoke
         if add {
+            self.check_not_locked(deps.as_ref(), env, token_id, &token)?;
             // reject expired data as invalid
             let expires = expires.unwrap_or_default();
             if expires.is_expired(&env.block) {
@@ -979,6 +2620,117 @@ where
         }
     }
 
+    /// fails if the token (or the whole collection) is not transferable right now
+    pub fn check_not_locked(
+        &self,
+        deps: Deps,
+        env: &Env,
+        token_id: &str,
+        token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        if self.soulbound(deps.storage)? || token.is_locked(&env.block) {
+            return Err(ContractError::Locked {});
+        }
+        if self.stakes.may_load(deps.storage, token_id)?.is_some() {
+            return Err(ContractError::Staked {});
+        }
+        Ok(())
+    }
+
+    /// records kept on the side, keyed by token_id, follow the token when it is renamed
+    pub fn move_token_records(
+        &self,
+        storage: &mut dyn Storage,
+        old_token_id: &str,
+        new_token_id: &str,
+    ) -> StdResult<()> {
+        if let Some(token) = self.tokens.may_load(storage, new_token_id)? {
+            if token.serial > 0 {
+                self.serials.save(
+                    storage,
+                    U64Key::from(token.serial),
+                    &new_token_id.to_string(),
+                )?;
+            }
+        }
+        if let Some(mut stake) = self.stakes.may_load(storage, old_token_id)? {
+            self.stakes.remove(storage, old_token_id);
+            stake.token_id = new_token_id.to_string();
+            self.stakes.save(storage, new_token_id, &stake)?;
+        }
+        if let Some(staked_time) = self.token_staked_time.may_load(storage, old_token_id)? {
+            self.token_staked_time.remove(storage, old_token_id);
+            self.token_staked_time
+                .save(storage, new_token_id, &staked_time)?;
+        }
+        let sends = self
+            .send_history
+            .prefix(old_token_id)
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (_, record) in sends {
+            self.send_history
+                .remove(storage, (old_token_id, U64Key::from(record.id)));
+            self.send_history
+                .save(storage, (new_token_id, U64Key::from(record.id)), &record)?;
+        }
+        Ok(())
+    }
+
+    /// a name a token is about to take, at buy or on a rename: held to the content policy and
+    /// not on the blocklist
+    pub fn check_new_name(&self, storage: &dyn Storage, name: &str) -> Result<(), ContractError> {
+        check_name(&self.content_policy(storage)?, name)?;
+        if self.blocked_names.has(storage, &name.to_lowercase()) {
+            return Err(ContractError::NameBlocked {});
+        }
+        Ok(())
+    }
+
+    /// fails if the whole collection, or this token, has been frozen
+    pub fn check_not_frozen(
+        &self,
+        storage: &dyn Storage,
+        token_id: Option<&str>,
+    ) -> Result<(), ContractError> {
+        if self.metadata_freeze.may_load(storage)?.is_some() {
+            return Err(ContractError::Frozen {});
+        }
+        if let Some(token_id) = token_id {
+            if self.frozen_tokens.has(storage, token_id) {
+                return Err(ContractError::Frozen {});
+            }
+        }
+        Ok(())
+    }
+
+    /// returns true iff the sender can change the name, description or status of the token.
+    /// approvals & operators are for transfers, and don't count here
+    pub fn check_can_edit_metadata(
+        &self,
+        deps: Deps,
+        info: &MessageInfo,
+        token: &TokenInfo<T>,
+    ) -> Result<(), ContractError> {
+        // owner can edit
+        if token.owner == info.sender {
+            return Ok(());
+        }
+        // delegates for this token can edit
+        if token.metadata_delegates.contains(&info.sender) {
+            return Ok(());
+        }
+        // delegates for all of the owner's tokens can edit
+        if self
+            .owner_metadata_delegates
+            .may_load(deps.storage, (&token.owner, &info.sender))?
+            .is_some()
+        {
+            return Ok(());
+        }
+        Err(ContractError::Unauthorized {})
+    }
+
     /// returns true iff the sender can transfer ownership of the token
     fn check_can_send(
         &self,
@@ -1042,11 +2794,13 @@ where
         name: Option<String>,
         description: Option<String>,
     ) -> Result<Response<C>, ContractError> {
-        self._set_name_description(deps, &env, &info, &token_id, &name, &description)?;
+        let (_, refund) =
+            self._set_name_description(deps, &env, &info, &token_id, &name, &description)?;
 
         if let Some(name_in) = name {
             if name_in.is_empty() {
                 Ok(Response::new()
+                    .add_messages(refund)
                     .add_attribute("action", "change_name")
                     .add_attribute("sender", info.sender)
                     .add_attribute("token_id", token_id)
@@ -1056,6 +2810,7 @@ where
                     ))
             } else {
                 Ok(Response::new()
+                    .add_messages(refund)
                     .add_attribute("action", "change_name")
                     .add_attribute("sender", info.sender)
                     .add_attribute("old_token_id", token_id)
@@ -1067,6 +2822,7 @@ where
             }
         } else {
             Ok(Response::new()
+                .add_messages(refund)
                 .add_attribute("action", "change_description")
                 .add_attribute("sender", info.sender)
                 .add_attribute("token_id", token_id)
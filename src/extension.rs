@@ -8,12 +8,119 @@ pub struct Trait {
     pub value: String,
 }
 
+/// The metadata fields that can be given as a path under a gateway prefix
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum UriField {
+    Image,
+    AnimationUrl,
+    ExternalUrl,
+}
+
+impl UriField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UriField::Image => "image",
+            UriField::AnimationUrl => "animation_url",
+            UriField::ExternalUrl => "external_url",
+        }
+    }
+}
+
+/// Gateway prefixes, put in front of any value of the field that isn't already a full
+/// ipfs:// or http(s) uri
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct UriPrefixes {
+    pub image: String,
+    pub animation_url: String,
+    pub external_url: String,
+}
+
+fn with_prefix(uri: &Option<String>, prefix: &str) -> Option<String> {
+    uri.as_ref().map(|u| {
+        if u.starts_with("http") {
+            u.clone()
+        } else if let Some(path) = u.strip_prefix("ipfs://") {
+            // gateway urls are stored as ipfs://, they go back out through the field's gateway
+            if prefix.is_empty() {
+                u.clone()
+            } else {
+                format!("{}{}", prefix, path)
+            }
+        } else {
+            format!("{}{}", prefix, u)
+        }
+    })
+}
+
+/// The metadata fields UpdateTokenMetadata can change
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataField {
+    Image,
+    ImageData,
+    ExternalUrl,
+    Description,
+    Attributes,
+    BackgroundColor,
+    AnimationUrl,
+    YoutubeUrl,
+}
+
+/// A field-level change to a token's metadata. fields left unset are kept
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct MetadataPatch {
+    pub image: Option<String>,
+    pub image_data: Option<String>,
+    pub external_url: Option<String>,
+    pub description: Option<String>,
+    pub attributes: Option<Vec<Trait>>,
+    pub background_color: Option<String>,
+    pub animation_url: Option<String>,
+    pub youtube_url: Option<String>,
+}
+
+impl MetadataPatch {
+    /// the fields this patch changes
+    pub fn fields(&self) -> Vec<MetadataField> {
+        let mut fields = vec![];
+        if self.image.is_some() {
+            fields.push(MetadataField::Image);
+        }
+        if self.image_data.is_some() {
+            fields.push(MetadataField::ImageData);
+        }
+        if self.external_url.is_some() {
+            fields.push(MetadataField::ExternalUrl);
+        }
+        if self.description.is_some() {
+            fields.push(MetadataField::Description);
+        }
+        if self.attributes.is_some() {
+            fields.push(MetadataField::Attributes);
+        }
+        if self.background_color.is_some() {
+            fields.push(MetadataField::BackgroundColor);
+        }
+        if self.animation_url.is_some() {
+            fields.push(MetadataField::AnimationUrl);
+        }
+        if self.youtube_url.is_some() {
+            fields.push(MetadataField::YoutubeUrl);
+        }
+        fields
+    }
+}
+
 pub trait MetaDataPersonalization {
     fn get_decision_trait(&self, trait_type: &str) -> Option<Trait>;
+    fn get_attributes(&self) -> Vec<Trait>;
     fn set_personalized_trait(&mut self, trait_type: &str, value: &str);
+    fn set_trait(&mut self, trait_type: &str, value: &str);
     fn set_status(&mut self, status: &str);
     fn get_status(&self) -> Option<String>;
     fn get_token_uri(&self) -> String;
+    fn set_token_uri(&mut self, token_uri: &str);
     fn get_image(&self, prefix: &str) -> Option<String>;
     fn get_image_raw(&self) -> Option<String>;
     fn set_image(&mut self, image: Option<String>);
@@ -21,6 +128,8 @@ pub trait MetaDataPersonalization {
     fn set_name(&mut self, image: Option<String>);
     fn get_description(&self) -> Option<String>;
     fn set_description(&mut self, image: Option<String>);
+    fn apply_patch(&mut self, patch: &MetadataPatch);
+    fn resolve_uris(&mut self, prefixes: &UriPrefixes);
 }
 
 pub trait MetaPersonalize {
@@ -55,9 +164,15 @@ impl MetaDataPersonalization for Metadata {
             None
         }
     }
+    fn get_attributes(&self) -> Vec<Trait> {
+        self.attributes.clone().unwrap_or_default()
+    }
     fn get_token_uri(&self) -> String {
         self.token_uri.clone()
     }
+    fn set_token_uri(&mut self, token_uri: &str) {
+        self.token_uri = token_uri.to_string()
+    }
 
     fn set_personalized_trait(&mut self, trait_type: &str, value: &str) {
         if let Some(attr_list) = &self.attributes {
@@ -78,6 +193,17 @@ impl MetaDataPersonalization for Metadata {
             self.attributes = Some(new_attr);
         }
     }
+    fn set_trait(&mut self, trait_type: &str, value: &str) {
+        let attributes = self.attributes.get_or_insert_with(Vec::new);
+        match attributes.iter_mut().find(|t| t.trait_type == trait_type) {
+            Some(existing) => existing.value = value.into(),
+            None => attributes.push(Trait {
+                display_type: None,
+                trait_type: trait_type.into(),
+                value: value.into(),
+            }),
+        }
+    }
     fn set_status(&mut self, status: &str) {
         self.current_status = Some(String::from(status))
     }
@@ -85,13 +211,12 @@ impl MetaDataPersonalization for Metadata {
         self.current_status.clone()
     }
     fn get_image(&self, prefix: &str) -> Option<String> {
-        return self.image.as_ref().map(|i| {
-            if i.starts_with("ipfs://") || i.starts_with("http") {
-                i.clone()
-            } else {
-                format!("{}{}", prefix, i)
-            }
-        });
+        with_prefix(&self.image, prefix)
+    }
+    fn resolve_uris(&mut self, prefixes: &UriPrefixes) {
+        self.image = with_prefix(&self.image, &prefixes.image);
+        self.animation_url = with_prefix(&self.animation_url, &prefixes.animation_url);
+        self.external_url = with_prefix(&self.external_url, &prefixes.external_url);
     }
     fn set_image(&mut self, image: Option<String>) {
         self.image = image
@@ -112,6 +237,33 @@ impl MetaDataPersonalization for Metadata {
     fn set_description(&mut self, description: Option<String>) {
         self.description = description
     }
+    fn apply_patch(&mut self, patch: &MetadataPatch) {
+        let patch = patch.clone();
+        if patch.image.is_some() {
+            self.image = patch.image;
+        }
+        if patch.image_data.is_some() {
+            self.image_data = patch.image_data;
+        }
+        if patch.external_url.is_some() {
+            self.external_url = patch.external_url;
+        }
+        if patch.description.is_some() {
+            self.description = patch.description;
+        }
+        if patch.attributes.is_some() {
+            self.attributes = patch.attributes;
+        }
+        if patch.background_color.is_some() {
+            self.background_color = patch.background_color;
+        }
+        if patch.animation_url.is_some() {
+            self.animation_url = patch.animation_url;
+        }
+        if patch.youtube_url.is_some() {
+            self.youtube_url = patch.youtube_url;
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
@@ -1,15 +1,24 @@
+mod audit;
+mod content;
 mod contract_tests;
 mod error;
 mod execute;
 mod extension;
+#[cfg(test)]
+mod mock_querier;
 //mod identity_digest;
 pub mod msg;
 mod query;
 //mod secp256k1;
 pub mod state;
+mod tax;
+mod uri;
 
 pub use crate::error::ContractError;
-pub use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, MintMsg, MinterResponse, QueryMsg};
+pub use crate::msg::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, MintMsg, MinterResponse, QueryMsg,
+    TokensWithInfoResponse,
+};
 
 use crate::state::Cw721Contract;
 //use cosmwasm_std::Order;
@@ -27,7 +36,9 @@ pub mod entry {
 
     //use crate::state::{token_owner_idx_change_dynamics, ChangeDynamicsIndexes};
     use cosmwasm_std::entry_point;
-    use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult};
+    use cosmwasm_std::{
+        Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, Response, StdResult,
+    };
 
     // This makes a conscious choice on the various generics used by the contract
     #[entry_point]
@@ -52,13 +63,19 @@ pub mod entry {
         tract.execute(deps, env, info, msg)
     }
 
+    #[entry_point]
+    pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+        let tract = Cw721Contract::<Extension, Empty>::default();
+        tract.reply(deps, env, msg)
+    }
+
     #[entry_point]
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         let tract = Cw721Contract::<Extension, Empty>::default();
         tract.query(deps, env, msg)
     }
     #[entry_point]
-    pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
         /*
                 let mut tract = Cw721Contract::<Extension, Empty>::default();
                 let change_dynamics_key = "change_dynamics";
@@ -72,7 +89,8 @@ pub mod entry {
                 };
                 tract.change_dynamics = IndexedMap::new(change_dynamics_key, change_dynamics_indexes);
         */
-        Ok(Response::default())
+        let tract = Cw721Contract::<Extension, Empty>::default();
+        tract.migrate(deps, env, msg)
     }
 }
 
@@ -0,0 +1,51 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+#[cfg(feature = "terra")]
+use cosmwasm_std::{to_binary, ContractResult, Decimal, SystemResult, Uint128};
+use cosmwasm_std::{Coin, OwnedDeps};
+#[cfg(feature = "terra")]
+use terra_cosmwasm::{TaxCapResponse, TaxRateResponse, TerraQuery, TerraQueryWrapper};
+
+/// tax rate the mock treasury charges on transfers
+#[cfg(feature = "terra")]
+pub const MOCK_TAX_RATE: u64 = 1; // percent
+/// tax cap the mock treasury applies to every denom
+#[cfg(feature = "terra")]
+pub const MOCK_TAX_CAP: u128 = 1_000_000;
+
+/// like cosmwasm_std::testing::mock_dependencies, but also answers the treasury queries
+/// the terra build makes when computing tax
+#[cfg(feature = "terra")]
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, MockQuerier<TerraQueryWrapper>> {
+    let querier = MockQuerier::<TerraQueryWrapper>::new(&[(MOCK_CONTRACT_ADDR, contract_balance)])
+        .with_custom_handler(|query| {
+            let res = match &query.query_data {
+                TerraQuery::TaxRate {} => to_binary(&TaxRateResponse {
+                    rate: Decimal::percent(MOCK_TAX_RATE),
+                }),
+                TerraQuery::TaxCap { .. } => to_binary(&TaxCapResponse {
+                    cap: Uint128::new(MOCK_TAX_CAP),
+                }),
+                _ => panic!("unexpected terra query {:?}", query),
+            };
+            SystemResult::Ok(ContractResult::from(res))
+        });
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier,
+    }
+}
+
+/// chains without a treasury module only need the bank balances
+#[cfg(not(feature = "terra"))]
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]),
+    }
+}
@@ -1,11 +1,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::extension::MetaDataPersonalization;
-use crate::state::{NftListing, NftTraitSummary};
+use crate::extension::{MetaDataPersonalization, MetadataPatch, UriField};
+use crate::state::{
+    ChangeKind, ChangePricing, ContentPolicy, MetadataFieldAccess, NftListing, NftTraitSummary,
+    SendErrorPolicy, SendRecord, SerialToken, Stake, TokenUri, TraitCount, UriPolicy, Withdrawal,
+};
 use crate::BuyExtension;
-use cosmwasm_std::Binary;
-use cw721::Expiration;
+use cosmwasm_std::{Binary, Coin, Decimal, ReplyOn, Uint128};
+use cw721::{AllNftInfoResponse, Expiration};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MigrateMsg {}
@@ -30,6 +33,9 @@ pub struct InstantiateMsg {
     pub change_multiplier: u64,
     /// max amount of tokens to issue
     pub max_issuance: u64,
+    /// commitment to the collection's images before any are sold, see SetProvenance
+    #[serde(default)]
+    pub provenance_hash: Option<String>,
 }
 
 /// This is like Cw721ExecuteMsg but we add a Mint command for an owner
@@ -45,10 +51,14 @@ where
     TransferNft { recipient: String, token_id: String },
     /// Send is a base message to transfer a token to a contract and trigger an action
     /// on the receiving contract.
+    /// If reply_on is set (and not never), the receive message is sent as a sub-message and the
+    /// outcome is kept in the send history. With `error` or `always`, a failing receiver is handled
+    /// as per the send error policy; with `success` it reverts the send, whatever the policy
     SendNft {
         contract: String,
         token_id: String,
         msg: Binary,
+        reply_on: Option<ReplyOn>,
     },
     /// Allows operator to transfer / send the token from the owner's account.
     /// If expiration is set, then this allowance has a time/height limit
@@ -74,8 +84,49 @@ where
     Burn { token_id: String },
     /// Allow a buyer to mint a NFT directly
     Buy(BuyMsg),
-    /// Owner function: Sends coins in the contract to admin
-    Sweep { denom: String },
+    /// Owner function: roll the weighted traits of the trait map on chain at buy. salt_hash is
+    /// the hex sha256 of a salt kept secret until RevealMintSalt, so buyers can't work out
+    /// their traits in advance. The token id is still picked from the signed attributes.
+    /// unset to go back to the signed attributes only
+    SetMintSalt { salt_hash: Option<String> },
+    /// Owner function: reveals the salt committed to by SetMintSalt, and picks the traits of up
+    /// to limit of the buys waiting on it. Buys stop rolling traits once it is revealed; call
+    /// again with the same salt until the "done" attribute is true
+    RevealMintSalt { salt: String, limit: Option<u32> },
+    /// Owner function: page sizes of the list queries
+    SetQueryLimits { default_limit: u32, max_limit: u32 },
+    /// Owner function: fixes the problems AuditIntegrity reports, a page at a time. Conflicts
+    /// (two tokens with the same uri) are reported but not fixed. token_count is set to what
+    /// the pages counted, so don't mint or burn until the last page is done.
+    /// The response data is an AuditIntegrityResponse with what was found and where to carry on
+    Repair {
+        start_after: Option<AuditCursor>,
+        limit: Option<u32>,
+    },
+    /// Owner function: adds a page of tokens written before the upgrade to the status index
+    /// and the trait counts, and gives those without a serial the next ones in token_id order.
+    /// Tokens already indexed are left as they are. Carry on from the "last_token_id"
+    /// attribute until "done" is true
+    BackfillIndexes {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Owner function: cap on the page size of AllNftInfoRange and OwnerTokensWithInfo, at least 1
+    SetInfoMaxLimit { limit: u32 },
+    /// Owner function: Sends coins in the contract to recipient (default: the admin).
+    /// amount unset sends the whole balance less tax. Not available once payees are set
+    Sweep {
+        denom: String,
+        amount: Option<Uint128>,
+        recipient: Option<String>,
+    },
+    /// Owner function: Sweeps the whole balance of every denom the contract holds
+    SweepAll { recipient: Option<String> },
+    /// Owner function: set who gets the contract's revenue. shares have to add up to 1.
+    /// Whatever the contract holds is paid out to the current payees first
+    SetPayees { payees: Vec<PayeeMsg> },
+    /// Pay out the contract's balance of denom to the payees. Anyone can call this
+    Distribute { denom: String },
     /// Owner function: change public key
     SetPublicKey { public_key: String },
     /// Owner function: change mint price (uluna)
@@ -84,6 +135,12 @@ where
     SetChangeTimesMultiplier { change_multiplier: u64 },
     /// Owner function: change change name price (uusd)
     SetChangeAmount { change_amount: u64 },
+    /// Owner function: set the pricing curve for renames or description changes (uusd).
+    /// replaces change amount & multiplier for that kind of change
+    SetChangePricing {
+        kind: ChangeKind,
+        pricing: ChangePricing,
+    },
     /// User message: allow owner to change status field of NFT
     SetTokenStatus { status: String, token_id: String },
     /// User message: allow owner to change name & description field of NFT
@@ -92,8 +149,99 @@ where
         name: Option<String>,
         token_id: String,
     },
-    /// Owner message: change prefix for images. defaults to ipfs://
+    /// Changes the given metadata fields of a token. Each field has to be editable by the sender,
+    /// see SetMetadataFieldAccess. Owners pay for a description change as with
+    /// SetTokenNameDescription. The "name" trait can't be changed here, it follows renames
+    UpdateTokenMetadata {
+        token_id: String,
+        patch: MetadataPatch,
+    },
+    /// Owner message: commits to the collection's images before sales begin. The hash is the hex
+    /// sha256 of the images (as they will be stored in image_uri) concatenated in their original
+    /// order. reveal_height lets the starting index be picked from that height on, without
+    /// waiting for the sell out. Can't be changed once the first token is minted
+    SetProvenance {
+        provenance_hash: String,
+        reveal_height: Option<u64>,
+    },
+    /// Picks the starting index from block data, once. The token minted serial-th gets the image at
+    /// (serial - 1 + starting_index) % collection_size of the committed order.
+    /// Anyone can call it once the collection has sold out or the reveal height is reached: the
+    /// first call records the block, and a second one in a later block picks the index
+    FinalizeStartingIndex {},
+    /// Owner message: freezes the metadata of every token, and stops minting, for good. Each call
+    /// also hashes the next `limit` tokens into the metadata hash; call it until the response
+    /// has a metadata_hash
+    FreezeMetadata { limit: Option<u32> },
+    /// Owner message: freezes the metadata of one token, for good
+    FreezeToken { token_id: String },
+    /// Owner message: who may change each metadata field. all fields default to admin-only
+    SetMetadataFieldAccess { access: MetadataFieldAccess },
+    /// Owner message: what to do when the receiver of a tracked SendNft fails. defaults to revert
+    SetSendErrorPolicy { policy: SendErrorPolicy },
+    /// Owner message: make every token in the collection non-transferable (or not)
+    SetSoulbound { soulbound: bool },
+    /// Lock a token so it can't be transferred, sent or approved. Token owner or contract owner.
+    /// The lock lifts by itself at expires. The token owner has to set it, the contract owner
+    /// can leave it unset to lock for good.
+    /// The token owner can only extend an active lock, with an expiry of the same kind, the
+    /// contract owner can change it any way
+    LockToken {
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Remove the lock on a token. The token owner has to wait for the lock to expire,
+    /// the contract owner can unlock at any time
+    UnlockToken { token_id: String },
+    /// Let another account use the token (set its status) without being able to transfer it.
+    /// Token owner or operator. Setting user to None removes the grant.
+    /// The grant is removed on transfer, and lapses once it expires
+    SetUser {
+        token_id: String,
+        user: Option<String>,
+        expires: Option<Expiration>,
+    },
+    /// Let another account edit the name, description & status (but not transfer).
+    /// With a token_id it applies to that token until it is transferred,
+    /// without one it applies to all of the sender's tokens
+    AddMetadataDelegate {
+        delegate: String,
+        token_id: Option<String>,
+    },
+    /// Remove a previously added metadata delegate
+    RemoveMetadataDelegate {
+        delegate: String,
+        token_id: Option<String>,
+    },
+    /// User message: stake a token inside the contract. It can't be transferred until it is unstaked,
+    /// and it can't be unstaked for `duration` seconds
+    Stake { token_id: String, duration: u64 },
+    /// User message: unstake a token once its lock-up period is over
+    Unstake { token_id: String },
+    /// Owner message: change prefix for images, same as SetUriPrefix for the image. empty by
+    /// default
     SetImagePrefix { prefix: String },
+    /// Owner message: rename a token and/or reset its status. No fees, and the owner's
+    /// next change doesn't cost more, but it is counted in the token's ChangeDynamics
+    ModerateToken {
+        token_id: String,
+        name: Option<String>,
+        status: Option<String>,
+    },
+    /// Owner message: names (ignoring case) tokens can't be bought or renamed as. A blocked
+    /// name has to match the whole new name, unlike the content policy's reserved words, which
+    /// are turned away wherever they appear as a word in the name
+    AddBlockedNames { names: Vec<String> },
+    /// Owner message: take names off the blocklist
+    RemoveBlockedNames { names: Vec<String> },
+    /// Owner message: max lengths of names, descriptions and statuses, what names can be made of
+    /// and the words they can't use
+    SetContentPolicy { policy: ContentPolicy },
+    /// Owner message: max length and allowed schemes of token_uri's and images
+    SetUriPolicy { policy: UriPolicy },
+    /// Owner message: change the gateway prefix of image, animation_url or external_url. ipfs://
+    /// values are served through it too
+    SetUriPrefix { field: UriField, prefix: String },
     /// Owner message: Set information about the NFT Collection
     SetNftContractInfo {
         description: Option<String>,
@@ -113,6 +261,12 @@ where
     SetNftContractKeybaseVerification { message: String },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayeeMsg {
+    pub address: String,
+    pub share: Decimal,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct MintMsg<T> {
     /// Unique ID of the NFT
@@ -155,13 +309,17 @@ pub enum QueryMsg {
         include_expired: Option<bool>,
     },
     /// List all operators that can access all of the owner's tokens
-    /// Return type: `ApprovedForAllResponse`
+    /// Return type: OperatorsPageResponse
     ApprovedForAll {
         owner: String,
         /// unset or false will filter out expired items, you must set to true to see them
         include_expired: Option<bool>,
         start_after: Option<String>,
+        start_before: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
+        /// next_cursor of the previous page
+        cursor: Option<Binary>,
     },
     /// Total number of tokens issued
     NumTokens {},
@@ -190,30 +348,78 @@ pub enum QueryMsg {
 
     /// With Enumerable extension.
     /// Returns all tokens owned by the given address, [] if unset.
-    /// Return type: TokensResponse.
+    /// Return type: TokensPageResponse.
     Tokens {
         owner: String,
         start_after: Option<String>,
+        start_before: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
+        /// next_cursor of the previous page
+        cursor: Option<Binary>,
     },
     /// With Enumerable extension.
     /// Requires pagination. Lists all token_ids controlled by the contract.
-    /// Return type: TokensResponse.
+    /// Return type: TokensPageResponse.
     AllTokens {
         start_after: Option<String>,
+        start_before: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
+        /// next_cursor of the previous page
+        cursor: Option<Binary>,
     },
+    /// Lists image/token_id, in image order. start_after and start_before are images
+    /// Return type: TokensPageResponse.
     AllImgTokens {
         start_after: Option<String>,
+        start_before: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
+        /// next_cursor of the previous page
+        cursor: Option<Binary>,
     },
     /// With Enumerable extension.
     /// Requires pagination. Lists all token_ids controlled by the contract.
-    /// Return type: TokensResponse.
+    /// Return type: TokensPageResponse.
     RangeTokens {
         start_after: Option<String>,
+        start_before: Option<String>,
+        limit: Option<u32>,
+        order: Option<Order>,
+        /// next_cursor of the previous page
+        cursor: Option<Binary>,
+    },
+    /// The token with this token_uri, in any spelling of it
+    /// Return type: TokenUri
+    TokenByUri { token_uri: String },
+    /// token_uri's and their tokens, in token_uri order. ipfs uris are listed by
+    /// their bare `<cid>/path`
+    /// Return type: TokenUrisResponse
+    AllTokenUris {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Cross-checks tokens, tokens_uri, image_uri, change_dynamics and token_count.
+    /// Pass the returned `next` back as start_after until it comes back empty
+    /// Return type: AuditIntegrityResponse
+    AuditIntegrity {
+        start_after: Option<AuditCursor>,
+        limit: Option<u32>,
+    },
+    /// The token minted serial-th. follows the token through renames
+    /// Return type: SerialToken
+    TokenBySerial { serial: u64 },
+    /// Tokens in the order they were minted
+    /// Return type: SerialTokensResponse
+    TokensByMintOrder {
+        start_after: Option<u64>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
+    /// Return the page sizes of the list queries
+    /// Return type: QueryLimits
+    QueryLimits {},
 
     /// Return the minter
     Minter {},
@@ -223,9 +429,77 @@ pub enum QueryMsg {
     MintAmount {},
     /// Return the change amount and multiplier
     ChangeDetails {},
+    /// AllNftInfo of a page of tokens, in token id order
+    /// Return type: TokensWithInfoResponse
+    AllNftInfoRange {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// AllNftInfo of a page of the owner's tokens
+    /// Return type: TokensWithInfoResponse
+    OwnerTokensWithInfo {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// unset or false will filter out expired approvals, you must set to true to see them
+        include_expired: Option<bool>,
+    },
+    /// Return the page size cap of AllNftInfoRange and OwnerTokensWithInfo
+    InfoMaxLimit {},
+    /// Tokens carrying the trait
+    /// Return type: TokensResponse
+    TokensByTrait {
+        trait_type: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Tokens whose current status is exactly status
+    /// Return type: TokensResponse
+    TokensByStatus {
+        status: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// How many tokens carry each trait, by (trait_type, value)
+    /// Return type: TraitCountsResponse
+    TraitCounts {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    /// How rare each of the token's traits is, and its overall rarity score
+    /// Return type: TokenRarityResponse
+    TokenRarity { token_id: String },
+    /// Whether buys roll their weighted traits on chain
+    /// Return type: bool
+    RandomTraits {},
+    /// Return the payees, their shares, and what has been paid out to them so far
+    /// Return type: PayeesResponse
+    Payees {},
+    /// Return the pricing curves for renames and description changes
+    /// Return type: ChangePricingResponse
+    ChangePricing {},
+    /// Return what the next change of this kind to the token will cost
+    /// Return type: Coin
+    QuoteChange { token_id: String, kind: ChangeKind },
     /// Return the total supply
     TotalSupply {},
-    /// Return the prefix for the images. defaults to ipfs://
+    /// Blocked names, lower-cased, in order
+    /// Return type: BlockedNamesResponse
+    BlockedNames {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Return the limits on names, descriptions and statuses
+    /// Return type: ContentPolicy
+    ContentPolicy {},
+    /// Return the limits on token_uri's and images
+    /// Return type: UriPolicy
+    UriPolicy {},
+    /// Return the gateway prefixes of all the uri fields
+    /// Return type: UriPrefixes
     ImagePrefix {},
     /// Returns top-level NFT metadata about the contract: `NFTContractInfoResponse`
     NftContractInfo {},
@@ -233,6 +507,198 @@ pub enum QueryMsg {
     NftContractTraitMap {},
     /// Returns top-level NFT metadata about the keybase signature:
     NftContractKeybaseVerification {},
+    /// Return if the whole collection is non-transferable
+    Soulbound {},
+    /// Return the lock on the token (if any)
+    /// Return type: Option<Expiration>
+    TokenLock { token_id: String },
+    /// Return the current user of the token (if any, and not expired)
+    /// Return type: UserOfResponse
+    UserOf { token_id: String },
+    /// Who can edit the token's metadata, besides its owner.
+    /// start_after & limit page through the owner's delegates
+    /// Return type: MetadataDelegatesResponse
+    MetadataDelegates {
+        token_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Tokens the owner currently has staked
+    /// Return type: StakedTokensResponse
+    StakedTokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Staking details of a token
+    /// Return type: StakeInfoResponse
+    StakeInfo { token_id: String },
+    /// The committed provenance hash and starting index
+    /// Return type: Option<Provenance>
+    ProvenanceInfo {},
+    /// Whether the metadata is frozen, and the metadata hash once it is computed
+    /// Return type: Option<MetadataFreeze>
+    ProvenanceHash {},
+    /// Whether the token's metadata can still change
+    /// Return type: bool
+    TokenFrozen { token_id: String },
+    /// Who may change each metadata field
+    /// Return type: MetadataFieldAccess
+    MetadataFieldAccess {},
+    /// Return the policy used when the receiver of a tracked SendNft fails
+    SendErrorPolicy {},
+    /// Tracked SendNft's of this token, oldest first
+    /// Return type: SendHistoryResponse
+    SendHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Payouts of the contract's funds (sweeps and distributions), oldest first.
+    /// Meant for the admin, but like all contract state it is public
+    /// Return type: WithdrawalsResponse
+    Withdrawals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+/// Which way a list query runs
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl From<Order> for cosmwasm_std::Order {
+    fn from(order: Order) -> Self {
+        match order {
+            Order::Ascending => cosmwasm_std::Order::Ascending,
+            Order::Descending => cosmwasm_std::Order::Descending,
+        }
+    }
+}
+
+/// cw721's TokensResponse, plus where the next page starts
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokensPageResponse {
+    pub tokens: Vec<String>,
+    /// pass as cursor to get the next page. unset on the last page
+    pub next_cursor: Option<Binary>,
+}
+
+/// The maps AuditIntegrity walks, in order
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditSection {
+    #[default]
+    Tokens,
+    TokensUri,
+    ImageUri,
+    ChangeDynamics,
+}
+
+impl AuditSection {
+    pub fn next(self) -> Option<AuditSection> {
+        match self {
+            AuditSection::Tokens => Some(AuditSection::TokensUri),
+            AuditSection::TokensUri => Some(AuditSection::ImageUri),
+            AuditSection::ImageUri => Some(AuditSection::ChangeDynamics),
+            AuditSection::ChangeDynamics => None,
+        }
+    }
+}
+
+/// Where an audit got to
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
+pub struct AuditCursor {
+    pub section: AuditSection,
+    /// last key checked in the section
+    pub key: Option<String>,
+    /// tokens walked so far, checked against token_count at the end of the tokens
+    pub tokens_seen: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrityIssue {
+    /// the token's token_uri doesn't point back at it
+    TokenUriMissing {
+        token_id: String,
+        token_uri: String,
+    },
+    /// the token's image doesn't point back at it
+    ImageUriMissing {
+        token_id: String,
+        image: String,
+    },
+    /// the token's token_uri is indexed to another token that has it too. left for the owner
+    /// to sort out, repair doesn't pick between them
+    TokenUriConflict {
+        token_id: String,
+        token_uri: String,
+        held_by: String,
+    },
+    /// the token's image is indexed to another token that has it too
+    ImageUriConflict {
+        token_id: String,
+        image: String,
+        held_by: String,
+    },
+    /// points at a token that is gone, or no longer has this token_uri
+    StaleTokenUri {
+        token_uri: String,
+        token_id: String,
+    },
+    /// points at a token that is gone, or no longer has this image
+    StaleImageUri {
+        image: String,
+        token_id: String,
+    },
+    /// change history of a token that is gone
+    StaleChangeDynamics {
+        token_id: String,
+    },
+    /// change history with the wrong token_id or owner
+    ChangeDynamicsMismatch {
+        token_id: String,
+    },
+    TokenCount {
+        stored: u64,
+        actual: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct AuditIntegrityResponse {
+    pub issues: Vec<IntegrityIssue>,
+    /// None once everything has been checked
+    pub next: Option<AuditCursor>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct BlockedNamesResponse {
+    pub names: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenUrisResponse {
+    pub tokens: Vec<TokenUri>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SerialTokensResponse {
+    pub tokens: Vec<SerialToken>,
+}
+
+/// cw721's ApprovedForAllResponse, plus where the next page starts
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct OperatorsPageResponse {
+    pub operators: Vec<cw721::Approval>,
+    /// pass as cursor to get the next page. unset on the last page
+    pub next_cursor: Option<Binary>,
 }
 
 /// Shows who can mint these tokens
@@ -240,3 +706,99 @@ pub enum QueryMsg {
 pub struct MinterResponse {
     pub minter: String,
 }
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PayeeInfo {
+    pub address: String,
+    pub share: Decimal,
+    /// total paid out to this payee, per denom
+    pub received: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PayeesResponse {
+    pub payees: Vec<PayeeInfo>,
+    /// payees that have since been taken off the list, with a share of 0
+    pub former_payees: Vec<PayeeInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ChangePricingResponse {
+    pub rename: ChangePricing,
+    pub description: ChangePricing,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct UserOfResponse {
+    pub user: Option<String>,
+    pub expires: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct MetadataDelegatesResponse {
+    /// delegates for this token only
+    pub token_delegates: Vec<String>,
+    /// delegates for all of the owner's tokens
+    pub owner_delegates: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StakedTokensResponse {
+    pub stakes: Vec<Stake>,
+    /// seconds the owner has had tokens staked, over completed stakes
+    pub staked_time: u64,
+    /// at most max_limit of the owner's tokens are looked at a page. pass as start_after to
+    /// carry on, unset once they all have been
+    pub next: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct StakeInfoResponse {
+    pub stake: Option<Stake>,
+    /// seconds the token has spent staked, including the current stake
+    pub staked_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct SendHistoryResponse {
+    pub history: Vec<SendRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenWithInfo<T> {
+    pub token_id: String,
+    pub info: AllNftInfoResponse<T>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokensWithInfoResponse<T> {
+    pub tokens: Vec<TokenWithInfo<T>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitCountsResponse {
+    pub counts: Vec<TraitCount>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TraitRarity {
+    pub trait_type: String,
+    pub value: String,
+    /// tokens carrying this trait
+    pub count: u64,
+    /// share of all tokens carrying this trait
+    pub frequency: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenRarityResponse {
+    pub token_id: String,
+    pub traits: Vec<TraitRarity>,
+    /// sum over the traits of 1 / frequency. the higher, the rarer
+    pub score: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct WithdrawalsResponse {
+    pub withdrawals: Vec<Withdrawal>,
+}
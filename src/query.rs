@@ -1,21 +1,31 @@
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use cosmwasm_std::{to_binary, Binary, BlockInfo, Deps, Env, Order, Pair, StdError, StdResult};
+use cosmwasm_std::{
+    to_binary, Addr, Binary, BlockInfo, Coin, Decimal, Deps, Env, Order, Pair, StdError, StdResult,
+    Storage,
+};
 
-use crate::extension::MetaDataPersonalization;
+use crate::extension::{MetaDataPersonalization, UriPrefixes};
+use crate::uri::uri_key;
 use cw0::maybe_addr;
 use cw721::{
     AllNftInfoResponse, ApprovedForAllResponse, ContractInfoResponse, CustomMsg, Cw721Query,
     Expiration, NftInfoResponse, NumTokensResponse, OwnerOfResponse, TokensResponse,
 };
-use cw_storage_plus::Bound;
-
-use crate::msg::{MinterResponse, QueryMsg};
-use crate::state::{Approval, ChangeDynamics, Cw721Contract, TokenInfo};
+use cw_storage_plus::{Bound, PrimaryKey, U64Key};
 
-const DEFAULT_LIMIT: u32 = 10;
-const MAX_LIMIT: u32 = 30;
+use crate::msg::{
+    AuditIntegrityResponse, BlockedNamesResponse, ChangePricingResponse, MetadataDelegatesResponse,
+    MinterResponse, OperatorsPageResponse, Order as SortOrder, PayeeInfo, PayeesResponse, QueryMsg,
+    SendHistoryResponse, SerialTokensResponse, StakeInfoResponse, StakedTokensResponse,
+    TokenRarityResponse, TokenUrisResponse, TokenWithInfo, TokensPageResponse,
+    TokensWithInfoResponse, TraitCountsResponse, TraitRarity, UserOfResponse, WithdrawalsResponse,
+};
+use crate::state::{
+    Approval, ChangeDynamics, ChangeKind, Cw721Contract, SendRecord, SerialToken, TokenInfo,
+    TokenUri, TraitCount, Withdrawal,
+};
 
 impl<'a, T, C> Cw721Query<T> for Cw721Contract<'a, T, C>
 where
@@ -32,22 +42,9 @@ where
     }
 
     fn nft_info(&self, deps: Deps, token_id: String) -> StdResult<NftInfoResponse<T>> {
-        let prefix = self.image_prefix(deps.storage)?;
+        let prefixes = self.uri_prefixes(deps.storage)?;
         let info = self.tokens.load(deps.storage, &token_id)?;
-        if let Some(image) = info.extension.get_image(&prefix) {
-            let mut extension = info.extension.clone();
-            extension.set_image(Some(image));
-
-            Ok(NftInfoResponse {
-                token_uri: info.token_uri,
-                extension,
-            })
-        } else {
-            Ok(NftInfoResponse {
-                token_uri: info.token_uri,
-                extension: info.extension,
-            })
-        }
+        Ok(to_nft_info(&prefixes, info))
     }
 
     fn owner_of(
@@ -73,22 +70,15 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<ApprovedForAllResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start_addr = maybe_addr(deps.api, start_after)?;
-        let start = start_addr.map(|addr| Bound::exclusive(addr.as_ref()));
-
-        let owner_addr = deps.api.addr_validate(&owner)?;
-        let res: StdResult<Vec<_>> = self
-            .operators
-            .prefix(&owner_addr)
-            .range(deps.storage, start, None, Order::Ascending)
-            .filter(|r| {
-                include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
-            })
-            .take(limit)
-            .map(parse_approval)
-            .collect();
-        Ok(ApprovedForAllResponse { operators: res? })
+        let args = PageArgs {
+            start_after,
+            limit,
+            ..PageArgs::default()
+        };
+        let page = self.operators_page(deps, env, owner, include_expired, args)?;
+        Ok(ApprovedForAllResponse {
+            operators: page.operators,
+        })
     }
 
     fn tokens(
@@ -98,22 +88,15 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = start_after.map(Bound::exclusive);
-
-        let owner_addr = deps.api.addr_validate(&owner)?;
-        let pks: Vec<_> = self
-            .tokens
-            .idx
-            .owner
-            .prefix(owner_addr)
-            .keys(deps.storage, start, None, Order::Ascending)
-            .take(limit)
-            .collect();
-
-        let res: Result<Vec<_>, _> = pks.iter().map(|v| String::from_utf8(v.to_vec())).collect();
-        let tokens = res.map_err(StdError::invalid_utf8)?;
-        Ok(TokensResponse { tokens })
+        let args = PageArgs {
+            start_after,
+            limit,
+            ..PageArgs::default()
+        };
+        let page = self.tokens_page(deps, owner, args)?;
+        Ok(TokensResponse {
+            tokens: page.tokens,
+        })
     }
 
     fn all_tokens(
@@ -122,16 +105,15 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
-        let start = start_after.map(Bound::exclusive);
-
-        let tokens: StdResult<Vec<String>> = self
-            .tokens
-            .range(deps.storage, start, None, Order::Ascending)
-            .take(limit)
-            .map(|item| item.map(|(k, _)| String::from_utf8_lossy(&k).to_string()))
-            .collect();
-        Ok(TokensResponse { tokens: tokens? })
+        let args = PageArgs {
+            start_after,
+            limit,
+            ..PageArgs::default()
+        };
+        let page = self.all_tokens_page(deps, args)?;
+        Ok(TokensResponse {
+            tokens: page.tokens,
+        })
     }
 
     fn all_nft_info(
@@ -141,27 +123,46 @@ where
         token_id: String,
         include_expired: bool,
     ) -> StdResult<AllNftInfoResponse<T>> {
-        //      let prefix = self.image_prefix.load(deps.storage)?;
-        let prefix = self.image_prefix(deps.storage)?;
+        let prefixes = self.uri_prefixes(deps.storage)?;
         let info = self.tokens.load(deps.storage, &token_id)?;
-        let extension = if let Some(image) = info.extension.get_image(&prefix) {
-            let mut extension = info.extension.clone();
-            extension.set_image(Some(image));
-            extension
-        } else {
-            info.extension.clone()
-        };
+        Ok(to_all_nft_info(
+            &env.block,
+            &prefixes,
+            info,
+            include_expired,
+        ))
+    }
+}
 
-        Ok(AllNftInfoResponse {
-            access: OwnerOfResponse {
-                owner: info.owner.to_string(),
-                approvals: humanize_approvals(&env.block, &info, include_expired),
-            },
-            info: NftInfoResponse {
-                token_uri: info.token_uri,
-                extension,
-            },
-        })
+/// the AllNftInfo of a token, with the image prefix applied
+fn to_all_nft_info<T>(
+    block: &BlockInfo,
+    prefixes: &UriPrefixes,
+    info: TokenInfo<T>,
+    include_expired: bool,
+) -> AllNftInfoResponse<T>
+where
+    T: Clone + MetaDataPersonalization,
+{
+    AllNftInfoResponse {
+        access: OwnerOfResponse {
+            owner: info.owner.to_string(),
+            approvals: humanize_approvals(block, &info, include_expired),
+        },
+        info: to_nft_info(prefixes, info),
+    }
+}
+
+/// the NftInfo of a token, with the gateway prefixes applied to its uri fields
+fn to_nft_info<T>(prefixes: &UriPrefixes, info: TokenInfo<T>) -> NftInfoResponse<T>
+where
+    T: MetaDataPersonalization,
+{
+    let mut extension = info.extension;
+    extension.resolve_uris(prefixes);
+    NftInfoResponse {
+        token_uri: info.token_uri,
+        extension,
     }
 }
 
@@ -202,35 +203,167 @@ where
                 owner,
                 include_expired,
                 start_after,
+                start_before,
                 limit,
-            } => to_binary(&self.all_approvals(
+                order,
+                cursor,
+            } => to_binary(&self.operators_page(
                 deps,
                 env,
                 owner,
                 include_expired.unwrap_or(false),
-                start_after,
-                limit,
+                PageArgs {
+                    start_after,
+                    start_before,
+                    limit,
+                    order,
+                    cursor,
+                },
             )?),
             QueryMsg::NumTokens {} => to_binary(&self.num_tokens(deps)?),
             QueryMsg::Tokens {
                 owner,
                 start_after,
+                start_before,
                 limit,
-            } => to_binary(&self.tokens(deps, owner, start_after, limit)?),
-            QueryMsg::AllTokens { start_after, limit } => {
-                to_binary(&self.all_tokens(deps, start_after, limit)?)
+                order,
+                cursor,
+            } => to_binary(&self.tokens_page(
+                deps,
+                owner,
+                PageArgs {
+                    start_after,
+                    start_before,
+                    limit,
+                    order,
+                    cursor,
+                },
+            )?),
+            QueryMsg::AllTokens {
+                start_after,
+                start_before,
+                limit,
+                order,
+                cursor,
             }
-            QueryMsg::AllImgTokens { start_after, limit } => {
-                to_binary(&self.all_img_tokens(deps, start_after, limit)?)
+            | QueryMsg::RangeTokens {
+                start_after,
+                start_before,
+                limit,
+                order,
+                cursor,
+            } => to_binary(&self.all_tokens_page(
+                deps,
+                PageArgs {
+                    start_after,
+                    start_before,
+                    limit,
+                    order,
+                    cursor,
+                },
+            )?),
+            QueryMsg::AllImgTokens {
+                start_after,
+                start_before,
+                limit,
+                order,
+                cursor,
+            } => to_binary(&self.all_img_tokens(
+                deps,
+                PageArgs {
+                    start_after,
+                    start_before,
+                    limit,
+                    order,
+                    cursor,
+                },
+            )?),
+            QueryMsg::TokenByUri { token_uri } => to_binary(&TokenUri {
+                token_id: self.tokens_uri.load(deps.storage, &uri_key(&token_uri))?,
+                token_uri,
+            }),
+            QueryMsg::AllTokenUris { start_after, limit } => {
+                to_binary(&self.all_token_uris(deps, start_after, limit)?)
             }
-            QueryMsg::RangeTokens { start_after, limit } => {
-                to_binary(&self.page_tokens(deps, start_after, limit)?)
+            QueryMsg::AuditIntegrity { start_after, limit } => {
+                let limit = self.page_limit(deps.storage, limit)?;
+                let page = self.audit(deps.storage, start_after, limit)?;
+                to_binary(&AuditIntegrityResponse {
+                    issues: page.issues,
+                    next: page.next,
+                })
             }
+            QueryMsg::TokenBySerial { serial } => to_binary(&SerialToken {
+                serial,
+                token_id: self.serials.load(deps.storage, U64Key::from(serial))?,
+            }),
+            QueryMsg::TokensByMintOrder {
+                start_after,
+                limit,
+                order,
+            } => to_binary(&self.tokens_by_mint_order(deps, start_after, limit, order)?),
+            QueryMsg::QueryLimits {} => to_binary(&self.query_limits(deps.storage)?),
             QueryMsg::PublicKey {} => to_binary(&self.public_key(deps.storage)?),
             QueryMsg::MintAmount {} => to_binary(&self.mint_amount(deps.storage)?),
             QueryMsg::ChangeDetails {} => to_binary(&self.change_details(deps.storage)?),
+            QueryMsg::AllNftInfoRange {
+                start_after,
+                limit,
+                include_expired,
+            } => to_binary(&self.all_nft_info_range(
+                deps,
+                env,
+                start_after,
+                limit,
+                include_expired.unwrap_or(false),
+            )?),
+            QueryMsg::OwnerTokensWithInfo {
+                owner,
+                start_after,
+                limit,
+                include_expired,
+            } => to_binary(&self.owner_tokens_with_info(
+                deps,
+                env,
+                owner,
+                start_after,
+                limit,
+                include_expired.unwrap_or(false),
+            )?),
+            QueryMsg::InfoMaxLimit {} => to_binary(&self.info_max_limit(deps.storage)?),
+            QueryMsg::TokensByTrait {
+                trait_type,
+                value,
+                start_after,
+                limit,
+            } => to_binary(&self.tokens_by_trait(deps, trait_type, value, start_after, limit)?),
+            QueryMsg::TokensByStatus {
+                status,
+                start_after,
+                limit,
+            } => to_binary(&self.tokens_by_status(deps, status, start_after, limit)?),
+            QueryMsg::TraitCounts { start_after, limit } => {
+                to_binary(&self.trait_counts(deps, start_after, limit)?)
+            }
+            QueryMsg::TokenRarity { token_id } => to_binary(&self.token_rarity(deps, token_id)?),
+            QueryMsg::RandomTraits {} => {
+                to_binary(&self.mint_salt_hash.may_load(deps.storage)?.is_some())
+            }
+            QueryMsg::Payees {} => to_binary(&self.query_payees(deps)?),
+            QueryMsg::ChangePricing {} => to_binary(&ChangePricingResponse {
+                rename: self.change_pricing(deps.storage, &ChangeKind::Rename)?,
+                description: self.change_pricing(deps.storage, &ChangeKind::Description)?,
+            }),
+            QueryMsg::QuoteChange { token_id, kind } => {
+                to_binary(&self.quote_change(deps, env, token_id, kind)?)
+            }
             QueryMsg::TotalSupply {} => to_binary(&self.max_issuance(deps.storage)?),
-            QueryMsg::ImagePrefix {} => to_binary(&self.image_prefix(deps.storage)?),
+            QueryMsg::BlockedNames { start_after, limit } => {
+                to_binary(&self.blocked_names(deps, start_after, limit)?)
+            }
+            QueryMsg::ContentPolicy {} => to_binary(&self.content_policy(deps.storage)?),
+            QueryMsg::UriPolicy {} => to_binary(&self.uri_policy(deps.storage)?),
+            QueryMsg::ImagePrefix {} => to_binary(&self.uri_prefixes(deps.storage)?),
             QueryMsg::NftContractInfo {} => to_binary(&self.nft_contract_info(deps.storage)?),
             QueryMsg::NftContractTraitMap {} => {
                 to_binary(&self.nft_contract_trait_map(deps.storage)?)
@@ -241,40 +374,614 @@ where
             QueryMsg::ChangeDynamics { token_id } => {
                 to_binary(&self.token_change_dynamics(deps, env, token_id)?)
             }
+            QueryMsg::Soulbound {} => to_binary(&self.soulbound(deps.storage)?),
+            QueryMsg::TokenLock { token_id } => {
+                to_binary(&self.tokens.load(deps.storage, &token_id)?.locked)
+            }
+            QueryMsg::UserOf { token_id } => to_binary(&self.user_of(deps, env, token_id)?),
+            QueryMsg::MetadataDelegates {
+                token_id,
+                start_after,
+                limit,
+            } => to_binary(&self.metadata_delegates(deps, token_id, start_after, limit)?),
+            QueryMsg::StakedTokens {
+                owner,
+                start_after,
+                limit,
+            } => to_binary(&self.staked_tokens(deps, owner, start_after, limit)?),
+            QueryMsg::StakeInfo { token_id } => to_binary(&self.stake_info(deps, env, token_id)?),
+            QueryMsg::ProvenanceInfo {} => to_binary(&self.provenance.may_load(deps.storage)?),
+            QueryMsg::ProvenanceHash {} => to_binary(&self.metadata_freeze.may_load(deps.storage)?),
+            QueryMsg::TokenFrozen { token_id } => to_binary(
+                &(self.metadata_freeze.may_load(deps.storage)?.is_some()
+                    || self.frozen_tokens.has(deps.storage, &token_id)),
+            ),
+            QueryMsg::MetadataFieldAccess {} => to_binary(&self.field_access(deps.storage)?),
+            QueryMsg::SendErrorPolicy {} => to_binary(&self.send_error_policy(deps.storage)?),
+            QueryMsg::SendHistory {
+                token_id,
+                start_after,
+                limit,
+            } => to_binary(&self.send_history(deps, token_id, start_after, limit)?),
+            QueryMsg::Withdrawals { start_after, limit } => {
+                to_binary(&self.withdrawals(deps, start_after, limit)?)
+            }
         }
     }
-    fn all_img_tokens(
+    fn query_payees(&self, deps: Deps) -> StdResult<PayeesResponse> {
+        let payee_info = |address: Addr, share: Decimal| -> StdResult<PayeeInfo> {
+            let received: StdResult<Vec<Coin>> = self
+                .payee_received
+                .prefix(&address)
+                .range(deps.storage, None, None, Order::Ascending)
+                .map(|item| {
+                    item.and_then(|(denom, amount)| {
+                        Ok(Coin {
+                            denom: String::from_utf8(denom)?,
+                            amount,
+                        })
+                    })
+                })
+                .collect();
+            Ok(PayeeInfo {
+                address: address.to_string(),
+                share,
+                received: received?,
+            })
+        };
+        let payees = self
+            .payees(deps.storage)?
+            .into_iter()
+            .map(|payee| payee_info(payee.address, payee.share))
+            .collect::<StdResult<Vec<_>>>()?;
+        let former_payees = self
+            .former_payees
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|address| payee_info(address, Decimal::zero()))
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(PayeesResponse {
+            payees,
+            former_payees,
+        })
+    }
+
+    fn quote_change(
         &self,
         deps: Deps,
+        env: Env,
+        token_id: String,
+        kind: ChangeKind,
+    ) -> StdResult<Coin> {
+        let change_dynamics = self.token_change_dynamics(deps, env, token_id)?;
+        let amount = self
+            .change_pricing(deps.storage, &kind)?
+            .price(change_dynamics.change_count)?;
+        Ok(Coin {
+            denom: "uusd".to_string(),
+            amount,
+        })
+    }
+
+    fn user_of(&self, deps: Deps, env: Env, token_id: String) -> StdResult<UserOfResponse> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        match token.active_user(&env.block) {
+            Some(user) => Ok(UserOfResponse {
+                user: Some(user.user.to_string()),
+                expires: Some(user.expires),
+            }),
+            None => Ok(UserOfResponse {
+                user: None,
+                expires: None,
+            }),
+        }
+    }
+
+    fn metadata_delegates(
+        &self,
+        deps: Deps,
+        token_id: String,
         start_after: Option<String>,
         limit: Option<u32>,
-    ) -> StdResult<TokensResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    ) -> StdResult<MetadataDelegatesResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start_addr = maybe_addr(deps.api, start_after)?;
+        let start = start_addr.map(|addr| Bound::exclusive(addr.as_ref()));
+
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let owner_delegates: StdResult<Vec<String>> = self
+            .owner_metadata_delegates
+            .prefix(&token.owner)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|k| String::from_utf8(k).map_err(StdError::invalid_utf8))
+            .collect();
+        Ok(MetadataDelegatesResponse {
+            token_delegates: token
+                .metadata_delegates
+                .iter()
+                .map(|delegate| delegate.to_string())
+                .collect(),
+            owner_delegates: owner_delegates?,
+        })
+    }
+
+    fn staked_tokens(
+        &self,
+        deps: Deps,
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<StakedTokensResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
         let start = start_after.map(Bound::exclusive);
 
-        let tokens: StdResult<Vec<String>> = self
-            .image_uri
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        // staked tokens stay with their owner, so walk the owner's tokens. no more than
+        // max_limit of them a page, however few are staked
+        let budget = self.query_limits(deps.storage)?.max_limit as usize;
+        let mut stakes = vec![];
+        let mut last_key = None;
+        let mut next = None;
+        let token_ids = self.tokens.idx.owner.prefix(owner_addr.clone()).keys(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        );
+        for (walked, token_id) in token_ids.enumerate() {
+            if stakes.len() == limit || walked == budget {
+                next = last_key;
+                break;
+            }
+            let token_id = String::from_utf8_lossy(&token_id).to_string();
+            if let Some(stake) = self.stakes.may_load(deps.storage, &token_id)? {
+                stakes.push(stake);
+            }
+            last_key = Some(token_id);
+        }
+        let staked_time = self
+            .owner_staked_time
+            .may_load(deps.storage, &owner_addr)?
+            .unwrap_or_default();
+        Ok(StakedTokensResponse {
+            stakes,
+            staked_time,
+            next,
+        })
+    }
+
+    fn stake_info(&self, deps: Deps, env: Env, token_id: String) -> StdResult<StakeInfoResponse> {
+        // make sure the token exists
+        self.tokens.load(deps.storage, &token_id)?;
+        let stake = self.stakes.may_load(deps.storage, &token_id)?;
+        let mut staked_time = self
+            .token_staked_time
+            .may_load(deps.storage, &token_id)?
+            .unwrap_or_default();
+        if let Some(stake) = &stake {
+            staked_time += env.block.time.seconds() - stake.staked_at;
+        }
+        Ok(StakeInfoResponse { stake, staked_time })
+    }
+
+    fn send_history(
+        &self,
+        deps: Deps,
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<SendHistoryResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+
+        let history: StdResult<Vec<SendRecord>> = self
+            .send_history
+            .prefix(&token_id)
             .range(deps.storage, start, None, Order::Ascending)
             .take(limit)
-            .map(|item| item.map(|(k, x)| format!("{}/{}", String::from_utf8_lossy(&k), x)))
+            .map(|item| item.map(|(_, record)| record))
             .collect();
-        Ok(TokensResponse { tokens: tokens? })
+        Ok(SendHistoryResponse { history: history? })
     }
-    fn page_tokens(
+    fn all_nft_info_range(
         &self,
         deps: Deps,
+        env: Env,
         start_after: Option<String>,
         limit: Option<u32>,
-    ) -> StdResult<TokensResponse> {
-        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        include_expired: bool,
+    ) -> StdResult<TokensWithInfoResponse<T>> {
+        let max_limit = self.info_max_limit(deps.storage)?;
+        let limit = limit.unwrap_or(max_limit).min(max_limit) as usize;
         let start = start_after.map(Bound::exclusive);
-        let tokens: StdResult<Vec<String>> = self
+        let prefixes = self.uri_prefixes(deps.storage)?;
+
+        let tokens: StdResult<Vec<TokenWithInfo<T>>> = self
             .tokens
             .range(deps.storage, start, None, Order::Ascending)
             .take(limit)
+            .map(|item| {
+                item.map(|(k, info)| TokenWithInfo {
+                    token_id: String::from_utf8_lossy(&k).to_string(),
+                    info: to_all_nft_info(&env.block, &prefixes, info, include_expired),
+                })
+            })
+            .collect();
+        Ok(TokensWithInfoResponse { tokens: tokens? })
+    }
+    fn owner_tokens_with_info(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        include_expired: bool,
+    ) -> StdResult<TokensWithInfoResponse<T>> {
+        let max_limit = self.info_max_limit(deps.storage)?;
+        let limit = limit.unwrap_or(max_limit).min(max_limit) as usize;
+        let start = start_after.map(Bound::exclusive);
+        let prefixes = self.uri_prefixes(deps.storage)?;
+
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let tokens: StdResult<Vec<TokenWithInfo<T>>> = self
+            .tokens
+            .idx
+            .owner
+            .prefix(owner_addr)
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(k, info)| TokenWithInfo {
+                    token_id: String::from_utf8_lossy(&k).to_string(),
+                    info: to_all_nft_info(&env.block, &prefixes, info, include_expired),
+                })
+            })
+            .collect();
+        Ok(TokensWithInfoResponse { tokens: tokens? })
+    }
+    fn blocked_names(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<BlockedNamesResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(Bound::exclusive);
+
+        let names: StdResult<Vec<String>> = self
+            .blocked_names
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
             .map(|item| item.map(|(k, _)| String::from_utf8_lossy(&k).to_string()))
             .collect();
-        Ok(TokensResponse { tokens: tokens? })
+        Ok(BlockedNamesResponse { names: names? })
+    }
+    fn all_token_uris(
+        &self,
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokenUrisResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(Bound::exclusive);
+
+        let tokens: StdResult<Vec<TokenUri>> = self
+            .tokens_uri
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                item.map(|(k, token_id)| TokenUri {
+                    token_uri: String::from_utf8_lossy(&k).to_string(),
+                    token_id,
+                })
+            })
+            .collect();
+        Ok(TokenUrisResponse { tokens: tokens? })
+    }
+    fn tokens_by_mint_order(
+        &self,
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<SortOrder>,
+    ) -> StdResult<SerialTokensResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let order: Order = order.unwrap_or_default().into();
+        let start = start_after.map(|serial| Bound::exclusive(U64Key::from(serial)));
+        let (min, max) = match order {
+            Order::Ascending => (start, None),
+            Order::Descending => (None, start),
+        };
+
+        let tokens: StdResult<Vec<SerialToken>> = self
+            .serials
+            .range(deps.storage, min, max, order)
+            .take(limit)
+            .map(|item| {
+                item.map(|(k, token_id)| {
+                    let mut bytes = [0u8; 8];
+                    bytes.copy_from_slice(&k);
+                    SerialToken {
+                        serial: u64::from_be_bytes(bytes),
+                        token_id,
+                    }
+                })
+            })
+            .collect();
+        Ok(SerialTokensResponse { tokens: tokens? })
+    }
+    fn tokens_by_trait(
+        &self,
+        deps: Deps,
+        trait_type: String,
+        value: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(Bound::exclusive);
+
+        let pks: Vec<_> = self
+            .token_traits
+            .prefix((&trait_type, &value))
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect();
+
+        let res: Result<Vec<_>, _> = pks.into_iter().map(String::from_utf8).collect();
+        let tokens = res.map_err(StdError::invalid_utf8)?;
+        Ok(TokensResponse { tokens })
+    }
+    fn tokens_by_status(
+        &self,
+        deps: Deps,
+        status: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(Bound::exclusive);
+
+        let pks: Vec<_> = self
+            .tokens
+            .idx
+            .status
+            .prefix(status)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect();
+
+        let res: Result<Vec<_>, _> = pks.into_iter().map(String::from_utf8).collect();
+        let tokens = res.map_err(StdError::invalid_utf8)?;
+        Ok(TokensResponse { tokens })
+    }
+    fn trait_counts(
+        &self,
+        deps: Deps,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    ) -> StdResult<TraitCountsResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|(trait_type, value)| {
+            Bound::exclusive((trait_type.as_str(), value.as_str()).joined_key())
+        });
+
+        let counts: StdResult<Vec<TraitCount>> = self
+            .trait_counts
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, count)| count))
+            .collect();
+        Ok(TraitCountsResponse { counts: counts? })
+    }
+    fn token_rarity(&self, deps: Deps, token_id: String) -> StdResult<TokenRarityResponse> {
+        let token = self.tokens.load(deps.storage, &token_id)?;
+        let num_tokens = self.token_count(deps.storage)?.max(1);
+        let mut score = Decimal::zero();
+        let mut traits = vec![];
+        for t in token.extension.get_attributes() {
+            let count = self
+                .trait_counts
+                .may_load(deps.storage, (&t.trait_type, &t.value))?
+                .map(|c| c.count)
+                .unwrap_or_default()
+                .max(1);
+            score = score + Decimal::from_ratio(num_tokens, count);
+            traits.push(TraitRarity {
+                trait_type: t.trait_type,
+                value: t.value,
+                count,
+                frequency: Decimal::from_ratio(count, num_tokens),
+            });
+        }
+        Ok(TokenRarityResponse {
+            token_id,
+            traits,
+            score,
+        })
+    }
+    fn withdrawals(
+        &self,
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<WithdrawalsResponse> {
+        let limit = self.page_limit(deps.storage, limit)?;
+        let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+
+        let withdrawals: StdResult<Vec<Withdrawal>> = self
+            .withdrawals
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, withdrawal)| withdrawal))
+            .collect();
+        Ok(WithdrawalsResponse {
+            withdrawals: withdrawals?,
+        })
+    }
+    /// how many items a list query returns
+    pub(crate) fn page_limit(&self, storage: &dyn Storage, limit: Option<u32>) -> StdResult<usize> {
+        let limits = self.query_limits(storage)?;
+        Ok(limit.unwrap_or(limits.default_limit).min(limits.max_limit) as usize)
+    }
+
+    /// the cursor, when there is one, takes over from start_after (or start_before when
+    /// running backwards)
+    fn page(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<Vec<u8>>,
+        start_before: Option<Vec<u8>>,
+        order: Option<SortOrder>,
+        cursor: Option<Binary>,
+        limit: Option<u32>,
+    ) -> StdResult<Page> {
+        let order: Order = order.unwrap_or_default().into();
+        let mut min = start_after.map(Bound::exclusive);
+        let mut max = start_before.map(Bound::exclusive);
+        if let Some(cursor) = cursor {
+            match order {
+                Order::Ascending => min = Some(Bound::exclusive(cursor.to_vec())),
+                Order::Descending => max = Some(Bound::exclusive(cursor.to_vec())),
+            }
+        }
+        Ok(Page {
+            min,
+            max,
+            order,
+            limit: self.page_limit(storage, limit)?,
+        })
+    }
+
+    fn operators_page(
+        &self,
+        deps: Deps,
+        env: Env,
+        owner: String,
+        include_expired: bool,
+        args: PageArgs,
+    ) -> StdResult<OperatorsPageResponse> {
+        let start_after = maybe_addr(deps.api, args.start_after)?;
+        let start_before = maybe_addr(deps.api, args.start_before)?;
+        let page = self.page(
+            deps.storage,
+            start_after.map(|addr| addr.as_bytes().to_vec()),
+            start_before.map(|addr| addr.as_bytes().to_vec()),
+            args.order,
+            args.cursor,
+            args.limit,
+        )?;
+
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let items: StdResult<Vec<Pair<Expiration>>> = self
+            .operators
+            .prefix(&owner_addr)
+            .range(deps.storage, page.min.clone(), page.max.clone(), page.order)
+            .filter(|r| {
+                include_expired || r.is_err() || !r.as_ref().unwrap().1.is_expired(&env.block)
+            })
+            .take(page.limit)
+            .collect();
+        let items = items?;
+        let next_cursor = page.next_cursor(items.iter().map(|(k, _)| k));
+        let operators: StdResult<Vec<_>> =
+            items.into_iter().map(|i| parse_approval(Ok(i))).collect();
+        Ok(OperatorsPageResponse {
+            operators: operators?,
+            next_cursor,
+        })
+    }
+
+    fn tokens_page(
+        &self,
+        deps: Deps,
+        owner: String,
+        args: PageArgs,
+    ) -> StdResult<TokensPageResponse> {
+        let page = self.page(
+            deps.storage,
+            args.start_after.map(String::into_bytes),
+            args.start_before.map(String::into_bytes),
+            args.order,
+            args.cursor,
+            args.limit,
+        )?;
+
+        let owner_addr = deps.api.addr_validate(&owner)?;
+        let pks: Vec<_> = self
+            .tokens
+            .idx
+            .owner
+            .prefix(owner_addr)
+            .keys(deps.storage, page.min.clone(), page.max.clone(), page.order)
+            .take(page.limit)
+            .collect();
+
+        let next_cursor = page.next_cursor(pks.iter());
+        let res: Result<Vec<_>, _> = pks.into_iter().map(String::from_utf8).collect();
+        let tokens = res.map_err(StdError::invalid_utf8)?;
+        Ok(TokensPageResponse {
+            tokens,
+            next_cursor,
+        })
+    }
+
+    fn all_tokens_page(&self, deps: Deps, args: PageArgs) -> StdResult<TokensPageResponse> {
+        let page = self.page(
+            deps.storage,
+            args.start_after.map(String::into_bytes),
+            args.start_before.map(String::into_bytes),
+            args.order,
+            args.cursor,
+            args.limit,
+        )?;
+
+        let keys: StdResult<Vec<Vec<u8>>> = self
+            .tokens
+            .range(deps.storage, page.min.clone(), page.max.clone(), page.order)
+            .take(page.limit)
+            .map(|item| item.map(|(k, _)| k))
+            .collect();
+        let keys = keys?;
+        let next_cursor = page.next_cursor(keys.iter());
+        let tokens = keys
+            .iter()
+            .map(|k| String::from_utf8_lossy(k).to_string())
+            .collect();
+        Ok(TokensPageResponse {
+            tokens,
+            next_cursor,
+        })
+    }
+
+    fn all_img_tokens(&self, deps: Deps, args: PageArgs) -> StdResult<TokensPageResponse> {
+        let page = self.page(
+            deps.storage,
+            args.start_after.map(String::into_bytes),
+            args.start_before.map(String::into_bytes),
+            args.order,
+            args.cursor,
+            args.limit,
+        )?;
+
+        let items: StdResult<Vec<Pair<String>>> = self
+            .image_uri
+            .range(deps.storage, page.min.clone(), page.max.clone(), page.order)
+            .take(page.limit)
+            .collect();
+        let items = items?;
+        let next_cursor = page.next_cursor(items.iter().map(|(k, _)| k));
+        let tokens = items
+            .iter()
+            .map(|(k, x)| format!("{}/{}", String::from_utf8_lossy(k), x))
+            .collect();
+        Ok(TokensPageResponse {
+            tokens,
+            next_cursor,
+        })
     }
 
     pub(crate) fn image_info(
@@ -282,23 +989,10 @@ where
         deps: Deps,
         image_uri: String,
     ) -> StdResult<NftInfoResponse<T>> {
-        let prefix = self.image_prefix(deps.storage)?;
-        let token_id = self.image_uri.load(deps.storage, &image_uri)?;
+        let prefixes = self.uri_prefixes(deps.storage)?;
+        let token_id = self.image_uri.load(deps.storage, &uri_key(&image_uri))?;
         let info = self.tokens.load(deps.storage, &token_id)?;
-        if let Some(image) = info.extension.get_image(&prefix) {
-            let mut extension = info.extension.clone();
-            extension.set_image(Some(image));
-
-            Ok(NftInfoResponse {
-                token_uri: info.token_uri,
-                extension,
-            })
-        } else {
-            Ok(NftInfoResponse {
-                token_uri: info.token_uri,
-                extension: info.extension,
-            })
-        }
+        Ok(to_nft_info(&prefixes, info))
     }
 
     pub(crate) fn token_change_dynamics(
@@ -321,6 +1015,7 @@ where
                     transfer_count: 0,
                     block_number: 0,
                     price_ceiling: Default::default(),
+                    moderation_count: 0,
                 })
             }
             Err(e) => Err(e),
@@ -328,6 +1023,36 @@ where
     }
 }
 
+/// the paging parameters shared by the list queries
+#[derive(Default)]
+pub(crate) struct PageArgs {
+    pub start_after: Option<String>,
+    pub start_before: Option<String>,
+    pub limit: Option<u32>,
+    pub order: Option<SortOrder>,
+    pub cursor: Option<Binary>,
+}
+
+/// where a page starts and ends, which way it runs, and how long it is
+struct Page {
+    min: Option<Bound>,
+    max: Option<Bound>,
+    order: Order,
+    limit: usize,
+}
+
+impl Page {
+    /// a full page might not be the last one, so it gets a cursor to carry on from its last key
+    fn next_cursor<'k>(&self, keys: impl Iterator<Item = &'k Vec<u8>>) -> Option<Binary> {
+        let keys: Vec<&Vec<u8>> = keys.collect();
+        if keys.len() == self.limit {
+            keys.last().map(|k| Binary::from(k.as_slice()))
+        } else {
+            None
+        }
+    }
+}
+
 fn parse_approval(item: StdResult<Pair<Expiration>>) -> StdResult<cw721::Approval> {
     item.and_then(|(k, expires)| {
         let spender = String::from_utf8(k)?;
@@ -3,11 +3,16 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
-use cosmwasm_std::{Addr, BlockInfo, Decimal, StdResult, Storage, Uint128};
+use cosmwasm_std::{
+    Addr, Binary, BlockInfo, Coin, Decimal, Empty, Fraction, OverflowError, OverflowOperation,
+    ReplyOn, StdResult, Storage, Uint128, Uint256,
+};
+use std::convert::TryFrom;
 
-use crate::extension::MetaDataPersonalization;
+use crate::extension::{MetaDataPersonalization, MetadataField, Trait, UriField, UriPrefixes};
+use crate::uri::uri_key;
 use cw721::{ContractInfoResponse, CustomMsg, Cw721, Expiration};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, U64Key};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct NftListing {
@@ -17,7 +22,12 @@ pub struct NftListing {
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 pub struct NftTraitSummary {
     pub label: String,
+    #[serde(default)]
     pub value: Decimal,
+    /// how likely the contract is to pick this label at buy. when weights are given `value` is
+    /// derived from them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<u32>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug, Default)]
@@ -67,11 +77,76 @@ where
     pub tokens: IndexedMap<'a, &'a str, TokenInfo<T>, TokenIndexes<'a, T>>,
     pub tokens_uri: IndexedMap<'a, &'a str, String, TokenIndexString<'a>>,
     pub image_uri: IndexedMap<'a, &'a str, String, TokenIndexString<'a>>,
+    /// the image prefix from before uri_prefixes, moved over by migrate
     pub image_prefix: Item<'a, String>,
     pub change_dynamics: IndexedMap<'a, &'a str, ChangeDynamics, ChangeDynamicsIndexes<'a>>,
+    pub send_error_policy: Item<'a, SendErrorPolicy>,
+    pub send_sequence: Item<'a, u64>,
+    /// tracked sends waiting on their reply, by reply id
+    pub pending_send: Map<'a, U64Key, PendingSend>,
+    /// stored as (token_id, send id)
+    pub send_history: Map<'a, (&'a str, U64Key), SendRecord>,
+    /// when set, no token in the collection can change hands
+    pub soulbound: Item<'a, bool>,
+    /// tokens currently staked
+    pub stakes: Map<'a, &'a str, Stake>,
+    /// seconds each token has spent staked, over completed stakes
+    pub token_staked_time: Map<'a, &'a str, u64>,
+    /// seconds each owner has had tokens staked, over completed stakes
+    pub owner_staked_time: Map<'a, &'a Addr, u64>,
+    /// who gets the contract's revenue, and in what share
+    pub payees: Item<'a, Vec<Payee>>,
+    /// Stored as (payee, denom) the total amount paid out to the payee
+    pub payee_received: Map<'a, (&'a Addr, &'a str), Uint128>,
+    /// payees that have been taken off the list, so what they received can still be looked up
+    pub former_payees: Item<'a, Vec<Addr>>,
+    pub withdrawal_sequence: Item<'a, u64>,
+    /// every payout of the contract's funds, by withdrawal id
+    pub withdrawals: Map<'a, U64Key, Withdrawal>,
+    /// hex sha256 of the admin's salt. while set, buys roll the weighted traits, which are
+    /// picked once the salt is revealed
+    pub mint_salt_hash: Item<'a, String>,
+    /// the salt once revealed, kept until every pending roll has been applied
+    pub revealed_salt: Item<'a, String>,
+    /// the buys waiting on the salt for their traits: serial -> the buy's share of the seed.
+    /// keyed by serial so renames don't lose the roll
+    pub pending_rolls: Map<'a, U64Key, Binary>,
+    /// stored as (trait_type, value) how many tokens have that trait
+    pub trait_counts: Map<'a, (&'a str, &'a str), TraitCount>,
+    /// stored as (trait_type, value, token_id)
+    pub token_traits: Map<'a, (&'a str, &'a str, &'a str), Empty>,
+    /// page size cap for the queries returning whole tokens
+    pub info_max_limit: Item<'a, u32>,
+    /// page sizes of the other list queries
+    pub query_limits: Item<'a, QueryLimits>,
+    pub mint_sequence: Item<'a, u64>,
+    /// serial -> current token id
+    pub serials: Map<'a, U64Key, String>,
+    /// who may change each metadata field with UpdateTokenMetadata
+    pub field_access: Item<'a, MetadataFieldAccess>,
+    /// set by the first FreezeMetadata, never removed
+    pub metadata_freeze: Item<'a, MetadataFreeze>,
+    /// tokens frozen one at a time
+    pub frozen_tokens: Map<'a, &'a str, Empty>,
+    pub provenance: Item<'a, Provenance>,
+    /// UriField name -> gateway prefix
+    pub uri_prefixes: Map<'a, &'a str, String>,
+    /// what token_uri's and images are accepted at mint
+    pub uri_policy: Item<'a, UriPolicy>,
+    /// limits on names, descriptions and statuses
+    pub content_policy: Item<'a, ContentPolicy>,
+    /// lower-cased names nobody can rename a token to
+    pub blocked_names: Map<'a, &'a str, Empty>,
+    pub rename_pricing: Item<'a, ChangePricing>,
+    pub description_pricing: Item<'a, ChangePricing>,
+    /// Stored as (owner, delegate) letting delegate edit metadata of all the owner's tokens
+    pub owner_metadata_delegates: Map<'a, (&'a Addr, &'a Addr), Empty>,
     pub(crate) _custom_response: PhantomData<C>,
 }
 
+/// page size cap for the queries returning whole tokens, until the admin sets one
+pub const DEFAULT_INFO_MAX_LIMIT: u32 = 10;
+
 // This is a signal, the implementations are in other files
 impl<'a, T, C> Cw721<T, C> for Cw721Contract<'a, T, C>
 where
@@ -82,7 +157,7 @@ where
 
 impl<T, C> Default for Cw721Contract<'static, T, C>
 where
-    T: Serialize + DeserializeOwned + Clone,
+    T: Serialize + DeserializeOwned + Clone + MetaDataPersonalization,
 {
     fn default() -> Self {
         Self::new(
@@ -107,13 +182,47 @@ where
             "change_multiplier",
             "change_dynamics",
             "change_dynamics__owner",
+            "send_error_policy",
+            "send_sequence",
+            "pending_send",
+            "send_history",
+            "soulbound",
+            "stakes",
+            "token_staked_time",
+            "owner_staked_time",
+            "owner_metadata_delegates",
+            "rename_pricing",
+            "description_pricing",
+            "payees",
+            "payee_received",
+            "former_payees",
+            "withdrawal_sequence",
+            "withdrawals",
+            "mint_salt_hash",
+            "revealed_salt",
+            "pending_rolls",
+            "trait_counts",
+            "token_traits",
+            "tokens__status",
+            "info_max_limit",
+            "query_limits",
+            "mint_sequence",
+            "serials",
+            "field_access",
+            "metadata_freeze",
+            "frozen_tokens",
+            "provenance",
+            "uri_prefixes",
+            "uri_policy",
+            "content_policy",
+            "blocked_names",
         )
     }
 }
 
 impl<'a, T, C> Cw721Contract<'a, T, C>
 where
-    T: Serialize + DeserializeOwned + Clone,
+    T: Serialize + DeserializeOwned + Clone + MetaDataPersonalization,
 {
     #[allow(clippy::too_many_arguments)]
     fn new(
@@ -138,9 +247,44 @@ where
         change_multiplier: &'a str,
         change_dynamics_key: &'a str,
         change_dynamics_owner_key: &'a str,
+        send_error_policy_key: &'a str,
+        send_sequence_key: &'a str,
+        pending_send_key: &'a str,
+        send_history_key: &'a str,
+        soulbound_key: &'a str,
+        stakes_key: &'a str,
+        token_staked_time_key: &'a str,
+        owner_staked_time_key: &'a str,
+        owner_metadata_delegates_key: &'a str,
+        rename_pricing_key: &'a str,
+        description_pricing_key: &'a str,
+        payees_key: &'a str,
+        payee_received_key: &'a str,
+        former_payees_key: &'a str,
+        withdrawal_sequence_key: &'a str,
+        withdrawals_key: &'a str,
+        mint_salt_hash_key: &'a str,
+        revealed_salt_key: &'a str,
+        pending_rolls_key: &'a str,
+        trait_counts_key: &'a str,
+        token_traits_key: &'a str,
+        tokens_status_key: &'a str,
+        info_max_limit_key: &'a str,
+        query_limits_key: &'a str,
+        mint_sequence_key: &'a str,
+        serials_key: &'a str,
+        field_access_key: &'a str,
+        metadata_freeze_key: &'a str,
+        frozen_tokens_key: &'a str,
+        provenance_key: &'a str,
+        uri_prefixes_key: &'a str,
+        uri_policy_key: &'a str,
+        content_policy_key: &'a str,
+        blocked_names_key: &'a str,
     ) -> Self {
         let indexes = TokenIndexes {
             owner: MultiIndex::new(token_owner_idx, tokens_key, tokens_owner_key),
+            status: MultiIndex::new(token_status_idx, tokens_key, tokens_status_key),
         };
         let uri_indexes = TokenIndexString {
             owner: MultiIndex::new(token_owner_idx_string, tokens_uri_key, tokens_uri_owner_key),
@@ -174,6 +318,39 @@ where
             trait_map: Item::new(trait_map_key),
             keybase_message: Item::new(keybase_message_key),
             change_dynamics: IndexedMap::new(change_dynamics_key, change_dynamics_indexes),
+            send_error_policy: Item::new(send_error_policy_key),
+            send_sequence: Item::new(send_sequence_key),
+            pending_send: Map::new(pending_send_key),
+            send_history: Map::new(send_history_key),
+            soulbound: Item::new(soulbound_key),
+            stakes: Map::new(stakes_key),
+            token_staked_time: Map::new(token_staked_time_key),
+            owner_staked_time: Map::new(owner_staked_time_key),
+            owner_metadata_delegates: Map::new(owner_metadata_delegates_key),
+            rename_pricing: Item::new(rename_pricing_key),
+            description_pricing: Item::new(description_pricing_key),
+            payees: Item::new(payees_key),
+            payee_received: Map::new(payee_received_key),
+            former_payees: Item::new(former_payees_key),
+            withdrawal_sequence: Item::new(withdrawal_sequence_key),
+            withdrawals: Map::new(withdrawals_key),
+            mint_salt_hash: Item::new(mint_salt_hash_key),
+            revealed_salt: Item::new(revealed_salt_key),
+            pending_rolls: Map::new(pending_rolls_key),
+            trait_counts: Map::new(trait_counts_key),
+            token_traits: Map::new(token_traits_key),
+            info_max_limit: Item::new(info_max_limit_key),
+            query_limits: Item::new(query_limits_key),
+            mint_sequence: Item::new(mint_sequence_key),
+            serials: Map::new(serials_key),
+            field_access: Item::new(field_access_key),
+            metadata_freeze: Item::new(metadata_freeze_key),
+            frozen_tokens: Map::new(frozen_tokens_key),
+            provenance: Item::new(provenance_key),
+            uri_prefixes: Map::new(uri_prefixes_key),
+            uri_policy: Item::new(uri_policy_key),
+            content_policy: Item::new(content_policy_key),
+            blocked_names: Map::new(blocked_names_key),
             _custom_response: PhantomData,
         }
     }
@@ -214,8 +391,50 @@ where
         })
     }
 
-    pub fn image_prefix(&self, storage: &dyn Storage) -> StdResult<String> {
-        Ok(self.image_prefix.may_load(storage)?.unwrap_or_default())
+    /// the pricing for this kind of change.
+    /// if none has been set, it is the original change_amount * (count / change_multiplier)
+    pub fn change_pricing(
+        &self,
+        storage: &dyn Storage,
+        kind: &ChangeKind,
+    ) -> StdResult<ChangePricing> {
+        let pricing = match kind {
+            ChangeKind::Rename => self.rename_pricing.may_load(storage)?,
+            ChangeKind::Description => self.description_pricing.may_load(storage)?,
+        };
+        match pricing {
+            Some(pricing) => Ok(pricing),
+            None => Ok(ChangePricing::Stepped {
+                amount: Uint128::from(self.change_amount(storage)?),
+                every: self.change_multiplier(storage)?,
+            }),
+        }
+    }
+
+    pub fn payees(&self, storage: &dyn Storage) -> StdResult<Vec<Payee>> {
+        Ok(self.payees.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn uri_policy(&self, storage: &dyn Storage) -> StdResult<UriPolicy> {
+        Ok(self.uri_policy.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn content_policy(&self, storage: &dyn Storage) -> StdResult<ContentPolicy> {
+        Ok(self.content_policy.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn uri_prefixes(&self, storage: &dyn Storage) -> StdResult<UriPrefixes> {
+        let prefix = |field: UriField| -> StdResult<String> {
+            Ok(self
+                .uri_prefixes
+                .may_load(storage, field.as_str())?
+                .unwrap_or_default())
+        };
+        Ok(UriPrefixes {
+            image: prefix(UriField::Image)?,
+            animation_url: prefix(UriField::AnimationUrl)?,
+            external_url: prefix(UriField::ExternalUrl)?,
+        })
     }
 
     pub fn nft_contract_info(&self, storage: &dyn Storage) -> StdResult<NftContractInfo> {
@@ -237,6 +456,125 @@ where
         Ok(self.keybase_message.may_load(storage)?.unwrap_or_default())
     }
 
+    pub fn field_access(&self, storage: &dyn Storage) -> StdResult<MetadataFieldAccess> {
+        Ok(self.field_access.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn send_error_policy(&self, storage: &dyn Storage) -> StdResult<SendErrorPolicy> {
+        Ok(self
+            .send_error_policy
+            .may_load(storage)?
+            .unwrap_or_default())
+    }
+
+    pub fn soulbound(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self.soulbound.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn info_max_limit(&self, storage: &dyn Storage) -> StdResult<u32> {
+        Ok(self
+            .info_max_limit
+            .may_load(storage)?
+            .unwrap_or(DEFAULT_INFO_MAX_LIMIT))
+    }
+
+    pub fn query_limits(&self, storage: &dyn Storage) -> StdResult<QueryLimits> {
+        Ok(self.query_limits.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn next_send_id(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+        let val = self.send_sequence.may_load(storage)?.unwrap_or_default() + 1;
+        self.send_sequence.save(storage, &val)?;
+        Ok(val)
+    }
+
+    pub fn next_serial(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+        let val = self.mint_sequence.may_load(storage)?.unwrap_or_default() + 1;
+        self.mint_sequence.save(storage, &val)?;
+        Ok(val)
+    }
+
+    pub fn next_withdrawal_id(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+        let val = self
+            .withdrawal_sequence
+            .may_load(storage)?
+            .unwrap_or_default()
+            + 1;
+        self.withdrawal_sequence.save(storage, &val)?;
+        Ok(val)
+    }
+
+    /// points the token's uri and image at its current id
+    pub fn index_token(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        token: &TokenInfo<T>,
+    ) -> StdResult<()> {
+        if let Some(token_uri) = &token.token_uri {
+            self.tokens_uri
+                .save(storage, &uri_key(token_uri), &token_id.to_string())?;
+        }
+        if let Some(image) = token.extension.get_image_raw() {
+            self.image_uri
+                .save(storage, &uri_key(&image), &token_id.to_string())?;
+        }
+        Ok(())
+    }
+
+    /// counts and indexes the token's traits
+    pub fn add_traits(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        traits: &[Trait],
+    ) -> StdResult<()> {
+        for t in traits {
+            self.trait_counts.update(
+                storage,
+                (t.trait_type.as_str(), t.value.as_str()),
+                |old| -> StdResult<TraitCount> {
+                    let mut count = old.unwrap_or_else(|| TraitCount {
+                        trait_type: t.trait_type.clone(),
+                        value: t.value.clone(),
+                        count: 0,
+                    });
+                    count.count += 1;
+                    Ok(count)
+                },
+            )?;
+            self.token_traits.save(
+                storage,
+                (t.trait_type.as_str(), t.value.as_str(), token_id),
+                &Empty {},
+            )?;
+        }
+        Ok(())
+    }
+
+    /// undoes add_traits
+    pub fn remove_traits(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &str,
+        traits: &[Trait],
+    ) -> StdResult<()> {
+        for t in traits {
+            let key = (t.trait_type.as_str(), t.value.as_str());
+            if let Some(mut count) = self.trait_counts.may_load(storage, key)? {
+                count.count = count.count.saturating_sub(1);
+                if count.count == 0 {
+                    self.trait_counts.remove(storage, key);
+                } else {
+                    self.trait_counts.save(storage, key, &count)?;
+                }
+            }
+            self.token_traits
+                .remove(storage, (t.trait_type.as_str(), t.value.as_str(), token_id));
+        }
+        Ok(())
+    }
+
     pub fn increment_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
         let val = self.token_count(storage)? + 1;
         self.token_count.save(storage, &val)?;
@@ -261,6 +599,231 @@ pub struct ChangeDynamics {
     pub transfer_count: u64,
     pub block_number: u64,
     pub price_ceiling: Uint128,
+    /// how many times the admin has had to rename the token or reset its status
+    #[serde(default)]
+    pub moderation_count: u64,
+}
+
+/// Characters a name can be made of
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CharClass {
+    /// letters of any alphabet
+    Letters,
+    AsciiLetters,
+    Digits,
+    Space,
+    /// ascii punctuation
+    Punctuation,
+}
+
+/// What owners can put in names, descriptions and statuses
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContentPolicy {
+    pub max_name_length: u32,
+    pub max_description_length: u32,
+    pub max_status_length: u32,
+    /// None allows anything but control and format characters
+    pub name_chars: Option<Vec<CharClass>>,
+    /// words (matched ignoring case) that can't appear in a name
+    pub reserved_words: Vec<String>,
+}
+
+impl Default for ContentPolicy {
+    fn default() -> Self {
+        ContentPolicy {
+            max_name_length: 64,
+            max_description_length: 1024,
+            max_status_length: 128,
+            name_chars: None,
+            reserved_words: vec![],
+        }
+    }
+}
+
+/// Limits on token_uri's and images
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UriPolicy {
+    pub max_length: u32,
+    /// schemes a uri may have once normalized, e.g. ["ipfs", "https"]. gateway urls are
+    /// normalized to ipfs://, so need "ipfs". None allows any
+    pub allowed_schemes: Option<Vec<String>>,
+}
+
+impl Default for UriPolicy {
+    fn default() -> Self {
+        UriPolicy {
+            max_length: 512,
+            allowed_schemes: None,
+        }
+    }
+}
+
+/// The collection's committed image order, and where minting starts in it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Provenance {
+    /// hex sha256 of the images in their original order
+    pub provenance_hash: String,
+    /// max_issuance when the provenance was set
+    pub collection_size: u64,
+    pub starting_index: Option<u64>,
+    /// height of the block the starting index was requested in. it is taken from a later one
+    pub starting_index_block: Option<u64>,
+    /// the starting index can be requested from this height on, even if not sold out
+    #[serde(default)]
+    pub reveal_height: Option<u64>,
+}
+
+/// Progress of the metadata hash started by FreezeMetadata. Tokens are hashed in token_id
+/// order, each into the previous hash: sha256(hash || token_id || token_uri || metadata json)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MetadataFreeze {
+    pub frozen_at: u64,
+    /// last token hashed
+    pub hashed_up_to: Option<String>,
+    pub tokens_hashed: u64,
+    pub hash: Binary,
+    /// true once every token has been hashed, `hash` is then the metadata hash
+    pub done: bool,
+}
+
+/// Who may change a metadata field after mint
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldAccess {
+    Immutable,
+    /// the contract owner
+    #[default]
+    Admin,
+    /// the contract owner, and the token owner & its metadata delegates
+    Owner,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MetadataFieldAccess {
+    pub image: FieldAccess,
+    pub image_data: FieldAccess,
+    pub external_url: FieldAccess,
+    pub description: FieldAccess,
+    pub attributes: FieldAccess,
+    pub background_color: FieldAccess,
+    pub animation_url: FieldAccess,
+    pub youtube_url: FieldAccess,
+}
+
+impl MetadataFieldAccess {
+    pub fn get(&self, field: MetadataField) -> FieldAccess {
+        match field {
+            MetadataField::Image => self.image,
+            MetadataField::ImageData => self.image_data,
+            MetadataField::ExternalUrl => self.external_url,
+            MetadataField::Description => self.description,
+            MetadataField::Attributes => self.attributes,
+            MetadataField::BackgroundColor => self.background_color,
+            MetadataField::AnimationUrl => self.animation_url,
+            MetadataField::YoutubeUrl => self.youtube_url,
+        }
+    }
+}
+
+/// What happens when the receiving contract of a tracked `SendNft` fails
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SendErrorPolicy {
+    /// fail the whole transaction, the token stays with the sender
+    #[default]
+    Revert,
+    /// keep the transfer, and log the error in the send history
+    KeepTransfer,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingSend {
+    pub token_id: String,
+    pub sender: Addr,
+    pub contract: Addr,
+    pub block_number: u64,
+    pub reply_on: ReplyOn,
+}
+
+/// A `SendNft` that was dispatched as a sub-message
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SendRecord {
+    pub id: u64,
+    pub sender: Addr,
+    pub contract: Addr,
+    pub block_number: u64,
+    pub reply_on: ReplyOn,
+    /// false if the receiver failed and the transfer was kept
+    pub success: bool,
+    /// data returned by the receiving contract
+    pub data: Option<Binary>,
+    pub error: Option<String>,
+}
+
+/// Which token a token_uri belongs to
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenUri {
+    pub token_uri: String,
+    pub token_id: String,
+}
+
+/// A token and its place in mint order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SerialToken {
+    pub serial: u64,
+    pub token_id: String,
+}
+
+/// Page sizes of the list queries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct QueryLimits {
+    /// used when the query doesn't ask for a limit
+    pub default_limit: u32,
+    /// the most a query can ask for
+    pub max_limit: u32,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        QueryLimits {
+            default_limit: 10,
+            max_limit: 30,
+        }
+    }
+}
+
+/// How many tokens carry a trait
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TraitCount {
+    pub trait_type: String,
+    pub value: String,
+    pub count: u64,
+}
+
+/// Funds paid out of the contract by a sweep or a distribution
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Withdrawal {
+    pub id: u64,
+    /// who asked for the payout
+    pub sender: Addr,
+    pub recipient: Addr,
+    /// what the recipient received
+    pub amount: Coin,
+    /// tax paid by the contract on top of the amount
+    pub tax: Uint128,
+    pub block_number: u64,
+}
+
+/// A token that is staked inside the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Stake {
+    pub owner: Addr,
+    pub token_id: String,
+    /// block time (in seconds) the token was staked
+    pub staked_at: u64,
+    /// the token can't be unstaked before this block time (in seconds)
+    pub unlocks_at: u64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -277,6 +840,50 @@ pub struct TokenInfo<T> {
 
     /// You can add any custom metadata here when you extend cw721-base
     pub extension: T,
+
+    /// token can't be transferred or approved until this expires
+    #[serde(default)]
+    pub locked: Option<Expiration>,
+
+    /// account that gets to use (but not move) the token. cleared on transfer
+    #[serde(default)]
+    pub user: Option<TokenUser>,
+
+    /// accounts that can edit the name/description/status of this token. cleared on transfer
+    #[serde(default)]
+    pub metadata_delegates: Vec<Addr>,
+
+    /// position in mint order, starting at 1. 0 for tokens minted before serials were kept,
+    /// until BackfillIndexes numbers them
+    #[serde(default)]
+    pub serial: u64,
+}
+
+impl<T> TokenInfo<T> {
+    pub fn is_locked(&self, block: &BlockInfo) -> bool {
+        self.locked
+            .map(|expires| !expires.is_expired(block))
+            .unwrap_or(false)
+    }
+
+    /// the user of the token, if the grant hasn't expired
+    pub fn active_user(&self, block: &BlockInfo) -> Option<&TokenUser> {
+        self.user.as_ref().filter(|user| !user.is_expired(block))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct TokenUser {
+    /// Account that can use the token (ie. set its status)
+    pub user: Addr,
+    /// When the grant expires (maybe Expiration::never)
+    pub expires: Expiration,
+}
+
+impl TokenUser {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -295,6 +902,81 @@ pub struct ChangeDetail {
     pub change_multiplier: u64,
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct Payee {
+    pub address: Addr,
+    /// share of the revenue. all the shares add up to 1
+    pub share: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// the name (and token_id) changes
+    Rename,
+    /// only the description changes
+    Description,
+}
+
+/// How much (in uusd) the nth change to a token costs. `count` is the # of changes done so far
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangePricing {
+    /// amount
+    Flat { amount: Uint128 },
+    /// base + step * count
+    Linear { base: Uint128, step: Uint128 },
+    /// base * ratio ^ count. counts past MAX_EXPONENT are priced as MAX_EXPONENT
+    Exponential { base: Uint128, ratio: Decimal },
+    /// steps[count], the last step is used once count runs past the end. free if empty
+    StepTable { steps: Vec<Uint128> },
+    /// amount * (count / every). the first `every` changes are free. free if every is 0
+    Stepped { amount: Uint128, every: u64 },
+}
+
+impl ChangePricing {
+    /// where exponential prices stop rising
+    pub const MAX_EXPONENT: u64 = 1_000;
+
+    pub fn price(&self, count: u64) -> StdResult<Uint128> {
+        match self {
+            ChangePricing::Flat { amount } => Ok(*amount),
+            ChangePricing::Linear { base, step } => {
+                Ok(base.checked_add(step.checked_mul(Uint128::from(count))?)?)
+            }
+            ChangePricing::Exponential { base, .. } if base.is_zero() => Ok(Uint128::zero()),
+            ChangePricing::Exponential { base, ratio } => {
+                // ratio ^ count by squaring, in the ratio's own fixed point
+                let one = Uint256::from(ratio.denominator());
+                let mut factor = one;
+                let mut square = Uint256::from(ratio.numerator());
+                let mut exponent = count.min(Self::MAX_EXPONENT);
+                while exponent > 0 {
+                    if exponent & 1 == 1 {
+                        factor = factor.checked_mul(square)?.checked_div(one)?;
+                    }
+                    exponent >>= 1;
+                    if exponent > 0 {
+                        square = square.checked_mul(square)?.checked_div(one)?;
+                    }
+                }
+                let price = Uint256::from(*base).checked_mul(factor)?.checked_div(one)?;
+                Ok(Uint128::try_from(price)
+                    .map_err(|_| OverflowError::new(OverflowOperation::Mul, base, ratio))?)
+            }
+            ChangePricing::StepTable { steps } => Ok(steps
+                .get(count as usize)
+                .or_else(|| steps.last())
+                .cloned()
+                .unwrap_or_default()),
+            ChangePricing::Stepped { amount, every } => match count.checked_div(*every) {
+                Some(times) => Ok(amount.checked_mul(Uint128::from(times))?),
+                None => Ok(Uint128::zero()),
+            },
+        }
+    }
+}
+
 impl Approval {
     pub fn is_expired(&self, block: &BlockInfo) -> bool {
         self.expires.is_expired(block)
@@ -307,6 +989,7 @@ where
 {
     // pk goes to second tuple element
     pub owner: MultiIndex<'a, (Addr, Vec<u8>), TokenInfo<T>>,
+    pub status: MultiIndex<'a, (String, Vec<u8>), TokenInfo<T>>,
 }
 
 impl<'a, T> IndexList<TokenInfo<T>> for TokenIndexes<'a, T>
@@ -314,13 +997,19 @@ where
     T: Serialize + DeserializeOwned + Clone,
 {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<TokenInfo<T>>> + '_> {
-        let v: Vec<&dyn Index<TokenInfo<T>>> = vec![&self.owner];
+        let v: Vec<&dyn Index<TokenInfo<T>>> = vec![&self.owner, &self.status];
         Box::new(v.into_iter())
     }
 }
 pub fn token_owner_idx<T>(d: &TokenInfo<T>, k: Vec<u8>) -> (Addr, Vec<u8>) {
     (d.owner.clone(), k)
 }
+pub fn token_status_idx<T: MetaDataPersonalization>(
+    d: &TokenInfo<T>,
+    k: Vec<u8>,
+) -> (String, Vec<u8>) {
+    (d.extension.get_status().unwrap_or_default(), k)
+}
 
 pub struct ChangeDynamicsIndexes<'a> {
     // pk goes to second tuple element
@@ -0,0 +1,56 @@
+use cosmwasm_std::{QuerierWrapper, StdResult, Uint128};
+
+/// the part of `amount` that goes to tax when the contract sends out all of `amount` of `denom`.
+/// On terra this comes from the treasury module
+#[cfg(feature = "terra")]
+pub fn compute_tax(querier: &QuerierWrapper, amount: Uint128, denom: &str) -> StdResult<Uint128> {
+    use cosmwasm_std::Decimal;
+    use terra_cosmwasm::TerraQuerier;
+
+    static DECIMAL_FRACTION: Uint128 = Uint128::new(1_000_000_000_000_000_000u128);
+
+    if denom == "uluna" {
+        return Ok(Uint128::zero());
+    }
+
+    let terra_querier = TerraQuerier::new(querier);
+    let tax_rate: Decimal = (terra_querier.query_tax_rate()?).rate;
+    let tax_cap: Uint128 = (terra_querier.query_tax_cap(denom)?).cap;
+    Ok(std::cmp::min(
+        amount.checked_sub(amount.multiply_ratio(
+            DECIMAL_FRACTION,
+            DECIMAL_FRACTION * tax_rate + DECIMAL_FRACTION,
+        ))?,
+        tax_cap,
+    ))
+}
+
+/// the tax the contract pays on top when it sends exactly `amount` of `denom`
+#[cfg(feature = "terra")]
+pub fn tax_on(querier: &QuerierWrapper, amount: Uint128, denom: &str) -> StdResult<Uint128> {
+    use terra_cosmwasm::TerraQuerier;
+
+    if denom == "uluna" {
+        return Ok(Uint128::zero());
+    }
+
+    let terra_querier = TerraQuerier::new(querier);
+    let tax_rate = (terra_querier.query_tax_rate()?).rate;
+    let tax_cap = (terra_querier.query_tax_cap(denom)?).cap;
+    Ok(std::cmp::min(amount * tax_rate, tax_cap))
+}
+
+/// chains without a treasury module don't tax transfers
+#[cfg(not(feature = "terra"))]
+pub fn compute_tax(
+    _querier: &QuerierWrapper,
+    _amount: Uint128,
+    _denom: &str,
+) -> StdResult<Uint128> {
+    Ok(Uint128::zero())
+}
+
+#[cfg(not(feature = "terra"))]
+pub fn tax_on(_querier: &QuerierWrapper, _amount: Uint128, _denom: &str) -> StdResult<Uint128> {
+    Ok(Uint128::zero())
+}
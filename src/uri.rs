@@ -0,0 +1,101 @@
+use crate::error::ContractError;
+use crate::state::UriPolicy;
+
+/// Checks a token_uri or image against the policy, and brings ipfs uris to one spelling,
+/// so the same content can't be claimed twice:
+///
+/// * `ipfs://<cid>/path` and `ipfs://ipfs/<cid>/path`
+/// * path gateways, `https://<host>/ipfs/<cid>/path`
+/// * subdomain gateways, `https://<cid>.ipfs.<host>/path`
+///
+/// all become `ipfs://<cid>/path`, without any query string. anything else is only trimmed
+pub fn normalize_uri(uri: &str, policy: &UriPolicy) -> Result<String, ContractError> {
+    let uri = uri.trim();
+    if uri.is_empty() {
+        return Err(ContractError::EmptyUri {});
+    }
+    if uri.len() > policy.max_length as usize {
+        return Err(ContractError::UriTooLong {
+            max_length: policy.max_length,
+        });
+    }
+    let normalized = match ipfs_path(uri) {
+        Some(path) if path.is_empty() => return Err(ContractError::EmptyUri {}),
+        Some(path) => format!("ipfs://{}", path),
+        None => uri.to_string(),
+    };
+    if let (Some(allowed), Some(scheme)) = (&policy.allowed_schemes, scheme(&normalized)) {
+        if !allowed.iter().any(|s| s.eq_ignore_ascii_case(scheme)) {
+            return Err(ContractError::SchemeNotAllowed {
+                scheme: scheme.to_string(),
+            });
+        }
+    }
+    Ok(normalized)
+}
+
+/// The key a uri is indexed under in tokens_uri and image_uri: the bare `<cid>/path` for ipfs
+/// uris, in any of the spellings above, and the trimmed uri otherwise.
+/// values stored before normalization came in get the same key as their normalized form
+pub fn uri_key(uri: &str) -> String {
+    let uri = uri.trim();
+    ipfs_path(uri).unwrap_or_else(|| uri.to_string())
+}
+
+/// `<cid>/path` of an ipfs uri, without a trailing '/'. None if it isn't one
+fn ipfs_path(uri: &str) -> Option<String> {
+    let uri = uri.split(['?', '#']).next().unwrap_or_default();
+    if let Some(rest) = strip_prefix_ignore_case(uri, "ipfs://") {
+        return Some(
+            strip_prefix_ignore_case(rest, "ipfs/")
+                .unwrap_or(rest)
+                .trim_end_matches('/')
+                .to_string(),
+        );
+    }
+    let rest = strip_prefix_ignore_case(uri, "https://")
+        .or_else(|| strip_prefix_ignore_case(uri, "http://"))?;
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    if let Some(cid_path) = strip_prefix_ignore_case(path, "ipfs/") {
+        return Some(cid_path.trim_end_matches('/').to_string());
+    }
+    // subdomain gateway, the CID is the first label
+    match host.split('.').collect::<Vec<_>>().as_slice() {
+        [cid, ipfs, _, ..] if !cid.is_empty() && ipfs.eq_ignore_ascii_case("ipfs") => Some(
+            format!("{}/{}", cid, path)
+                .trim_end_matches('/')
+                .to_string(),
+        ),
+        _ => None,
+    }
+}
+
+fn strip_prefix_ignore_case<'s>(s: &'s str, prefix: &str) -> Option<&'s str> {
+    if s.len() >= prefix.len()
+        && s.is_char_boundary(prefix.len())
+        && s[..prefix.len()].eq_ignore_ascii_case(prefix)
+    {
+        Some(&s[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// the scheme of an absolute uri
+fn scheme(uri: &str) -> Option<&str> {
+    let i = uri.find(':')?;
+    let scheme = &uri[..i];
+    let valid = scheme
+        .chars()
+        .next()
+        .map(|c| c.is_ascii_alphabetic())
+        .unwrap_or(false)
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+    if valid {
+        Some(scheme)
+    } else {
+        None
+    }
+}